@@ -1,7 +1,8 @@
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
 use glyphweave::{
-	AlgorithmKind, CanvasConfig, CloudRequest, FontSizeSpec, RenderOptions, ShapeConfig,
-	StyleConfig, WordEntry, generate, load_default_embedded_font,
+	AlgorithmKind, CanvasConfig, CloudRequest, CollisionMode, ColorStrategy, FontSizeSpec,
+	PlacementMode, RenderOptions, ShapeConfig, ShapeSource, SizeScaling, StyleConfig, SvgTextMode,
+	WordEntry, generate, load_default_embedded_font,
 };
 use std::sync::Arc;
 
@@ -32,25 +33,32 @@ fn bench_layouts(c: &mut Criterion) {
 						height: 520,
 						margin: 12,
 					},
-					shape: ShapeConfig {
-						text: "RUST".to_string(),
-						font_size: FontSizeSpec::AutoFit,
-					},
+					shape: ShapeConfig::new(
+						ShapeSource::Text("RUST".to_string()),
+						FontSizeSpec::AutoFit,
+					),
 					words: words.clone(),
 					style: StyleConfig {
 						font_size_range: 12..=28,
+						size_scaling: SizeScaling::Uniform,
 						padding: 0,
 						colors: vec!["#111111".to_string(), "#2277aa".to_string()],
-						rotations: vec![glyphweave::core::model::Rotation::Deg0],
+						color_strategy: ColorStrategy::Random,
+						rotations: vec![glyphweave::core::model::Rotation::DEG0],
+						rotation_range: None,
+						collision: CollisionMode::BoundingBox,
 					},
 					algorithm,
+					placement: PlacementMode::Weighted,
 					ratio_threshold: 0.75,
 					max_try_count: 5_000,
 					seed: Some(42),
 					font: Arc::clone(&font),
+					fallback_fonts: Vec::new(),
 					render: RenderOptions {
 						show_progress: false,
 						debug_mask_out: None,
+						svg_text: SvgTextMode::Text,
 					},
 				};
 				black_box(generate(req).expect("generation should succeed"));
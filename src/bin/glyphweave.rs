@@ -2,21 +2,33 @@
 mod cli;
 
 use clap::Parser;
-use cli::args::{CliAlgorithm, CliArgs, PaletteKind, collect_words, parse_shape_size_text};
-use cli::config::load_merged_config;
-use cli::palette::resolve_colors;
+use cli::args::{
+	Cli, CliAlgorithm, CliArgs, CliCollision, CliColorStrategy, CliCommand, CliPlacement,
+	CliSizeScaling, CliSvgText, MaskArgs, PaletteArgs, PaletteKind, RenderArgs, ShapeArgs,
+	collect_words, parse_shape_size_text, shape_source,
+};
+use cli::config::{FileConfig, load_merged_config};
+use cli::palette::{generate_palette, resolve_colors};
 use env_logger::Builder;
 use glyphweave::core::error::GlyphWeaveError;
 use glyphweave::core::model::{
-	CanvasConfig, CloudRequest, FontSizeSpec, RenderOptions, ShapeConfig, StyleConfig,
+	CanvasConfig, CloudLayout, CloudRequest, FontSizeSpec, FontSource, RasterOptions,
+	RenderOptions, ShapeConfig, ShapeSource, StyleConfig,
 };
+use glyphweave::export::{load_layout_json, save_layout_json};
+use glyphweave::font::font_family_name;
 use glyphweave::font::{discover_system_font_candidates, load_system_font_from_candidates};
+use glyphweave::mask::{resolve_shape_mask, save_mask_image, total_usable_area};
+use glyphweave::render::{
+	render_layout_raster, render_layout_svg, render_raster, save_raster_image,
+};
 use glyphweave::{
-	generate, load_default_embedded_font, load_font_from_file, rotations_from_degrees,
+	FontChain, FontFace, generate, load_default_embedded_font, load_font_from_file,
+	rotations_from_degrees,
 };
-use log::{error, info};
+use log::{error, info, warn};
 use std::io::{IsTerminal, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::Arc;
 
@@ -29,11 +41,36 @@ const DEFAULT_PALETTE_BASE: &str = "#3B82F6";
 const DEFAULT_PALETTE_SIZE: usize = 6;
 
 fn main() -> ExitCode {
-	let args = CliArgs::parse();
+	let cli = Cli::parse();
 
-	setup_logging(args.verbose);
+	let outcome = match cli.command {
+		Some(CliCommand::Generate(args)) => {
+			setup_logging(args.verbose);
+			run(*args)
+		}
+		Some(CliCommand::Render(args)) => {
+			setup_logging(args.verbose);
+			run_render(args)
+		}
+		Some(CliCommand::Mask(args)) => {
+			setup_logging(args.verbose);
+			run_mask(args)
+		}
+		Some(CliCommand::Fonts) => {
+			setup_logging(false);
+			run_fonts()
+		}
+		Some(CliCommand::Palette(args)) => {
+			setup_logging(false);
+			run_palette(args)
+		}
+		None => {
+			setup_logging(cli.generate.verbose);
+			run(cli.generate)
+		}
+	};
 
-	match run(args) {
+	match outcome {
 		Ok(()) => ExitCode::SUCCESS,
 		Err(err) => {
 			let code = map_error_to_exit_code(&err);
@@ -46,36 +83,43 @@ fn main() -> ExitCode {
 fn run(args: CliArgs) -> Result<(), GlyphWeaveError> {
 	let config = load_merged_config(args.config.as_deref())?;
 
-	let canvas_size = args
-		.canvas_size
-		.or_else(|| config.canvas_size_tuple())
-		.unwrap_or(DEFAULT_CANVAS_SIZE);
-	let canvas_margin = args
-		.canvas_margin
-		.or(config.canvas_margin)
-		.unwrap_or(DEFAULT_CANVAS_MARGIN);
+	let canvas = resolve_canvas(args.canvas_size, args.canvas_margin, &config);
 	let word_size_range = args
 		.word_size_range
 		.or_else(|| config.word_size_tuple())
 		.unwrap_or(DEFAULT_WORD_SIZE_RANGE);
+	let size_scaling = args
+		.size_scaling
+		.or(config.size_scaling_enum()?)
+		.unwrap_or(CliSizeScaling::Uniform);
 	let rotations = args
 		.rotations
 		.clone()
 		.or_else(|| config.rotations.clone())
 		.unwrap_or_else(|| vec![0]);
+	let rotation_range = args
+		.rotation_range
+		.or_else(|| config.rotation_range_tuple());
 
-	let shape_size = match (&args.shape_size, &config.text_size) {
-		(Some(size), _) => size.clone(),
-		(None, Some(size_text)) => parse_shape_size_text(size_text).map_err(|err| {
-			GlyphWeaveError::InvalidConfig(format!("invalid text_size in config: {err}"))
-		})?,
-		(None, None) => FontSizeSpec::AutoFit,
-	};
+	let shape = shape_config(&args.shape, &config)?;
 
 	let algorithm = args
 		.algorithm
 		.or(config.algorithm_enum()?)
 		.unwrap_or(CliAlgorithm::FastGrid);
+	let collision = args
+		.collision
+		.or(config.collision_enum()?)
+		.unwrap_or(CliCollision::Bbox);
+	let placement = args
+		.placement
+		.or(config.placement_enum()?)
+		.unwrap_or(CliPlacement::Weighted);
+	let repeats = args.repeats.or(config.repeats).unwrap_or(1);
+	let svg_text = args
+		.svg_text
+		.or(config.svg_text_enum()?)
+		.unwrap_or(CliSvgText::Text);
 
 	let palette = args
 		.palette
@@ -98,6 +142,12 @@ fn run(args: CliArgs) -> Result<(), GlyphWeaveError> {
 		palette_size,
 	)?;
 
+	let color_by = args
+		.color_by
+		.or(config.color_by_enum()?)
+		.unwrap_or(CliColorStrategy::Random);
+	let color_image = args.color_image.clone().or(config.color_image.clone());
+
 	let ratio = args.threshold.or(config.ratio).unwrap_or(DEFAULT_RATIO);
 	let max_tries = args
 		.max_tries
@@ -110,69 +160,67 @@ fn run(args: CliArgs) -> Result<(), GlyphWeaveError> {
 		config.no_progress.unwrap_or(false)
 	};
 
-	let font_path = args.font_path.as_ref().or(config.font.as_ref());
-	let font = if let Some(path) = font_path {
-		info!("Using user-provided font: {}", path.display());
-		load_font_from_file(path)?
-	} else {
-		match load_default_embedded_font() {
-			Ok(font) => {
-				info!("Using embedded Noto Sans SC font");
-				font
-			}
-			Err(embedded_error) => {
-				let candidates = discover_system_font_candidates();
-				let (font, selected) = if args.choose_system_font {
-					let selected =
-						select_system_font_candidate(&candidates, args.choose_system_font)?;
-					let font = load_font_from_file(&selected)?;
-					(font, selected)
-				} else {
-					load_system_font_from_candidates(&candidates)?
-				};
-				info!(
-					"Embedded font unavailable ({}), using system font: {}",
-					embedded_error,
-					selected.display()
-				);
-				font
-			}
-		}
+	let (font, font_source) = resolve_font(
+		args.font_path.as_ref().or(config.font.as_ref()),
+		args.choose_system_font,
+	)?;
+	let fallback_paths = fallback_font_paths(&args.fallback_fonts, &config);
+	let fallback_fonts = load_fallback_fonts(fallback_paths)?;
+
+	let raster = RasterOptions {
+		scale: args.scale.or(config.scale).unwrap_or(1.0),
+		background: args.background.clone().or(config.background.clone()),
 	};
 
 	let words = collect_words(&args)?;
 
 	let request = CloudRequest {
-		canvas: CanvasConfig {
-			width: canvas_size.0,
-			height: canvas_size.1,
-			margin: canvas_margin,
-		},
-		shape: ShapeConfig {
-			text: args.shape_text,
-			font_size: shape_size,
-		},
+		canvas,
+		shape,
 		words,
 		style: StyleConfig {
 			font_size_range: word_size_range.0..=word_size_range.1,
+			size_scaling: size_scaling.into(),
 			padding: 0,
 			colors,
+			color_strategy: color_by.with_image(color_image),
 			rotations: rotations_from_degrees(&rotations)?,
+			rotation_range: rotation_range.map(|(min, max)| min..=max),
+			collision: collision.into(),
 		},
 		algorithm: algorithm.into(),
+		placement: placement.with_repeats(repeats),
 		ratio_threshold: ratio,
 		max_try_count: max_tries,
 		seed,
 		font: Arc::new(font),
+		fallback_fonts,
 		render: RenderOptions {
 			show_progress: !no_progress,
-			debug_mask_out: args.debug_mask_out,
+			debug_mask_out: None,
+			svg_text: svg_text.into(),
 		},
 	};
 
-	let output_path = args.output;
+	let output_path = args
+		.output
+		.ok_or_else(|| GlyphWeaveError::InvalidConfig("--output is required".to_string()))?;
+	let canvas = request.canvas.clone();
+	let fonts = request.font_chain();
 	let result = generate(request)?;
-	std::fs::write(&output_path, result.svg)?;
+	if is_raster_output(&output_path) {
+		let image = render_raster(&canvas, &result.placements, &fonts, &raster)?;
+		save_raster_image(&image, &output_path)?;
+	} else {
+		std::fs::write(&output_path, &result.svg)?;
+	}
+	if let Some(layout_path) = &args.layout_out {
+		let fallback_sources = fallback_paths.iter().map(|path| layout_font_source(path));
+		let layout = CloudLayout::new(&canvas, font_family_name(fonts.primary()), &result)
+			.with_fonts(font_source, fallback_sources.collect());
+		save_layout_json(&layout, layout_path)?;
+		info!("Wrote layout -> {}", layout_path.display());
+	}
 
 	info!(
 		"Generated {} words, fill ratio {:.2}% (seed={}) -> {}",
@@ -181,10 +229,255 @@ fn run(args: CliArgs) -> Result<(), GlyphWeaveError> {
 		result.stats.seed,
 		output_path.display()
 	);
+	if !result.unplaced_words.is_empty() {
+		warn!(
+			"{} words could not be placed: {}",
+			result.unplaced_words.len(),
+			result.unplaced_words.join(", ")
+		);
+	}
+
+	Ok(())
+}
+
+fn run_mask(args: MaskArgs) -> Result<(), GlyphWeaveError> {
+	let config = load_merged_config(args.config.as_deref())?;
+	let canvas = resolve_canvas(args.canvas_size, args.canvas_margin, &config);
+	let shape = shape_config(&args.shape, &config)?;
+	let (font, _) = resolve_font(
+		args.font_path.as_ref().or(config.font.as_ref()),
+		args.choose_system_font,
+	)?;
+	let fonts = FontChain::from(font).with_fallbacks(load_fallback_fonts(fallback_font_paths(
+		&args.fallback_fonts,
+		&config,
+	))?);
+
+	let (mask, shape_font_size) = resolve_shape_mask(&canvas, &shape, &fonts)?;
+	save_mask_image(&mask, &args.output)?;
+
+	let mut out = std::io::stdout().lock();
+	let total = canvas.width * canvas.height;
+	let usable = total_usable_area(&mask);
+	writeln!(out, "canvas: {}x{}", canvas.width, canvas.height)?;
+	if matches!(shape.source, ShapeSource::Text(_)) {
+		writeln!(out, "shape font size: {shape_font_size}")?;
+	}
+	writeln!(
+		out,
+		"usable area: {usable} / {total} px ({:.2}%)",
+		usable as f32 / total.max(1) as f32 * 100.0
+	)?;
+	info!("Wrote mask -> {}", args.output.display());
+
+	Ok(())
+}
+
+fn run_fonts() -> Result<(), GlyphWeaveError> {
+	let mut out = std::io::stdout().lock();
+	let candidates = discover_system_font_candidates();
+	if candidates.is_empty() {
+		warn!("No system fonts discovered");
+	}
+	for path in candidates {
+		match load_font_from_file(&path) {
+			Ok(font) => writeln!(out, "{}\t{}", font_family_name(&font), path.display())?,
+			Err(err) => warn!("{err}"),
+		}
+	}
+
+	Ok(())
+}
+
+fn run_palette(args: PaletteArgs) -> Result<(), GlyphWeaveError> {
+	let colors = generate_palette(
+		args.palette,
+		args.palette_base.as_deref().unwrap_or(DEFAULT_PALETTE_BASE),
+		args.palette_size.unwrap_or(DEFAULT_PALETTE_SIZE),
+	)?;
+	let mut out = std::io::stdout().lock();
+	for color in colors {
+		writeln!(out, "{color}")?;
+	}
+
+	Ok(())
+}
+
+fn run_render(args: RenderArgs) -> Result<(), GlyphWeaveError> {
+	let mut layout = load_layout_json(&args.layout)?;
+	let fonts = render_fonts(&args, &layout)?;
+	if !layout.matches_font(&fonts) {
+		warn!(
+			"layout was measured with '{}' but is drawn with '{}'; words may overlap or clip",
+			layout.font_family,
+			font_family_name(fonts.primary())
+		);
+	}
+
+	if args.word_colors.is_some() || args.palette.is_some() {
+		let colors = resolve_colors(
+			args.word_colors,
+			args.palette.unwrap_or(PaletteKind::Auto),
+			args.palette_base.as_deref().unwrap_or(DEFAULT_PALETTE_BASE),
+			args.palette_size.unwrap_or(DEFAULT_PALETTE_SIZE),
+		)?;
+		layout.recolor(&colors);
+	}
+
+	if is_raster_output(&args.output) {
+		let raster = RasterOptions {
+			scale: args.scale.unwrap_or(1.0),
+			background: args.background,
+		};
+		let image = render_layout_raster(&layout, &fonts, &raster)?;
+		save_raster_image(&image, &args.output)?;
+	} else {
+		let svg_text = args.svg_text.unwrap_or(CliSvgText::Text);
+		std::fs::write(
+			&args.output,
+			render_layout_svg(&layout, &fonts, svg_text.into())?,
+		)?;
+	}
+
+	info!(
+		"Rendered {} placements from {} -> {}",
+		layout.placements.len(),
+		args.layout.display(),
+		args.output.display()
+	);
 
 	Ok(())
 }
 
+fn resolve_canvas(
+	canvas_size: Option<(usize, usize)>,
+	canvas_margin: Option<usize>,
+	config: &FileConfig,
+) -> CanvasConfig {
+	let (width, height) = canvas_size
+		.or_else(|| config.canvas_size_tuple())
+		.unwrap_or(DEFAULT_CANVAS_SIZE);
+	CanvasConfig {
+		width,
+		height,
+		margin: canvas_margin
+			.or(config.canvas_margin)
+			.unwrap_or(DEFAULT_CANVAS_MARGIN),
+	}
+}
+
+fn shape_config(args: &ShapeArgs, config: &FileConfig) -> Result<ShapeConfig, GlyphWeaveError> {
+	let mut shape = ShapeConfig::new(shape_source(args)?, resolve_shape_size(args, config)?);
+	if let Some(line_spacing) = args.line_spacing.or(config.line_spacing) {
+		shape.line_spacing = line_spacing;
+	}
+	if let Some(align) = args.text_align.or(config.text_align_enum()?) {
+		shape.align = align.into();
+	}
+	if let Some(path) = args.shape_font.as_ref().or(config.shape_font.as_ref()) {
+		info!("Using shape font: {}", path.display());
+		shape.font = Some(FontSource::Path(path.clone()));
+	}
+	Ok(shape)
+}
+
+fn resolve_shape_size(
+	args: &ShapeArgs,
+	config: &FileConfig,
+) -> Result<FontSizeSpec, GlyphWeaveError> {
+	match (&args.shape_size, &config.text_size) {
+		(Some(size), _) => Ok(size.clone()),
+		(None, Some(size_text)) => parse_shape_size_text(size_text).map_err(|err| {
+			GlyphWeaveError::InvalidConfig(format!("invalid text_size in config: {err}"))
+		}),
+		(None, None) => Ok(FontSizeSpec::AutoFit),
+	}
+}
+
+// Also returns where the font came from, so saved layouts can load it again.
+fn resolve_font(
+	font_path: Option<&PathBuf>,
+	choose_system_font: bool,
+) -> Result<(FontFace, FontSource), GlyphWeaveError> {
+	if let Some(path) = font_path {
+		info!("Using user-provided font: {}", path.display());
+		return Ok((load_font_from_file(path)?, layout_font_source(path)));
+	}
+
+	match load_default_embedded_font() {
+		Ok(font) => {
+			info!("Using embedded Noto Sans SC font");
+			Ok((font, FontSource::Embedded))
+		}
+		Err(embedded_error) => {
+			let candidates = discover_system_font_candidates();
+			let (font, selected) = if choose_system_font {
+				let selected = select_system_font_candidate(&candidates, choose_system_font)?;
+				let font = load_font_from_file(&selected)?;
+				(font, selected)
+			} else {
+				load_system_font_from_candidates(&candidates)?
+			};
+			info!(
+				"Embedded font unavailable ({}), using system font: {}",
+				embedded_error,
+				selected.display()
+			);
+			Ok((font, FontSource::Path(selected)))
+		}
+	}
+}
+
+// Layouts may be rendered from another directory, so relative paths are
+// stored absolute.
+fn layout_font_source(path: &Path) -> FontSource {
+	FontSource::Path(std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()))
+}
+
+// Any font option replaces the fonts recorded in the layout.
+fn render_fonts(args: &RenderArgs, layout: &CloudLayout) -> Result<FontChain, GlyphWeaveError> {
+	let overridden =
+		args.font_path.is_some() || args.choose_system_font || !args.fallback_fonts.is_empty();
+	if !overridden && let Some(fonts) = layout.load_fonts()? {
+		info!("Using the fonts recorded in the layout");
+		return Ok(fonts);
+	}
+
+	let (font, _) = resolve_font(args.font_path.as_ref(), args.choose_system_font)?;
+	Ok(FontChain::from(font).with_fallbacks(load_fallback_fonts(&args.fallback_fonts)?))
+}
+
+// Command-line fallbacks replace the config list rather than extending it,
+// like every other option.
+fn fallback_font_paths<'a>(args: &'a [PathBuf], config: &'a FileConfig) -> &'a [PathBuf] {
+	if args.is_empty() {
+		config.fallback_fonts.as_deref().unwrap_or_default()
+	} else {
+		args
+	}
+}
+
+fn load_fallback_fonts(paths: &[PathBuf]) -> Result<Vec<Arc<FontFace>>, GlyphWeaveError> {
+	paths
+		.iter()
+		.map(|path| {
+			info!("Using fallback font: {}", path.display());
+			load_font_from_file(path).map(Arc::new)
+		})
+		.collect()
+}
+
+fn is_raster_output(path: &Path) -> bool {
+	path.extension()
+		.and_then(|ext| ext.to_str())
+		.is_some_and(|ext| {
+			matches!(
+				ext.to_ascii_lowercase().as_str(),
+				"png" | "jpg" | "jpeg" | "webp"
+			)
+		})
+}
+
 fn map_error_to_exit_code(error: &GlyphWeaveError) -> u8 {
 	match error {
 		GlyphWeaveError::InvalidConfig(_) => 2,
@@ -1,11 +1,137 @@
-use clap::{Parser, ValueEnum};
+use crate::cli::word_table::{
+	WordColumns, apply_word_override, is_stdin, parse_word_table, read_word_source,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use glyphweave::core::error::GlyphWeaveError;
-use glyphweave::core::model::{AlgorithmKind, FontSizeSpec, WordEntry};
+use glyphweave::core::model::{
+	AlgorithmKind, CollisionMode, ColorStrategy, FillRule, FontSizeSpec, ImageMaskConfig,
+	MaskChannel, PhraseOptions, PlacementMode, ShapeLayer, ShapeSource, SizeScaling, SvgMaskSource,
+	SvgTextMode, TextAlign, TextOptions, WordEntry,
+};
+use glyphweave::text::words_from_text;
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(
+	author,
+	version,
+	about,
+	long_about = None,
+	args_conflicts_with_subcommands = true,
+	subcommand_negates_reqs = true
+)]
+pub struct Cli {
+	#[command(subcommand)]
+	pub command: Option<CliCommand>,
+
+	#[command(flatten)]
+	pub generate: CliArgs,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CliCommand {
+	/// Generate a word cloud (same as running without a subcommand)
+	Generate(Box<CliArgs>),
+	/// Render a layout saved with --layout-out without placing words again
+	Render(RenderArgs),
+	/// Write only the shape mask and print its area stats
+	Mask(MaskArgs),
+	/// List discovered system fonts with their family names
+	Fonts,
+	/// Print the colors a palette generates
+	Palette(PaletteArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct ShapeArgs {
+	#[arg(
+		short = 't',
+		long = "text",
+		required_unless_present_any = ["mask_image", "mask_svg", "mask_path", "shape_layers"],
+		help = "Shape text; '\\n' starts a new line"
+	)]
+	pub shape_text: Option<String>,
+
+	#[arg(
+		long = "line-spacing",
+		help = "Line spacing of multi-line shape text, as a multiple of the line height"
+	)]
+	pub line_spacing: Option<f32>,
+
+	#[arg(
+		long = "text-align",
+		value_enum,
+		help = "Alignment of multi-line shape text"
+	)]
+	pub text_align: Option<CliTextAlign>,
+
+	#[arg(
+		long = "mask-image",
+		conflicts_with_all = ["shape_text", "mask_svg", "mask_path"],
+		help = "PNG/JPEG image whose silhouette is used as the shape"
+	)]
+	pub mask_image: Option<PathBuf>,
+
+	#[arg(
+		long = "mask-svg",
+		conflicts_with_all = ["shape_text", "mask_path"],
+		help = "SVG document whose filled area is used as the shape"
+	)]
+	pub mask_svg: Option<PathBuf>,
+
+	#[arg(
+		long = "mask-path",
+		conflicts_with = "shape_text",
+		help = "SVG path data (the 'd' attribute) used as the shape"
+	)]
+	pub mask_path: Option<String>,
+
+	#[arg(
+		long = "shape-layers",
+		conflicts_with_all = ["shape_text", "mask_image", "mask_svg", "mask_path"],
+		help = "JSON file listing positioned shapes combined by union or subtraction"
+	)]
+	pub shape_layers: Option<PathBuf>,
+
+	#[arg(
+		long = "mask-fill-rule",
+		value_enum,
+		help = "Fill rule applied to --mask-path"
+	)]
+	pub mask_fill_rule: Option<CliFillRule>,
+
+	#[arg(
+		long = "mask-threshold",
+		help = "Channel threshold (0-255) separating shape from background"
+	)]
+	pub mask_threshold: Option<u8>,
+
+	#[arg(
+		long = "mask-channel",
+		value_enum,
+		help = "Image channel used for --mask-image (auto picks alpha when transparent)"
+	)]
+	pub mask_channel: Option<CliMaskChannel>,
+
+	#[arg(
+		long = "mask-invert",
+		default_value_t = false,
+		help = "Use the image background instead of the silhouette"
+	)]
+	pub mask_invert: bool,
+
+	#[arg(long = "text-size", value_parser = parse_shape_size)]
+	pub shape_size: Option<FontSizeSpec>,
+
+	#[arg(
+		long = "shape-font",
+		help = "Font for the shape text, e.g. a heavy display face; words keep --font"
+	)]
+	pub shape_font: Option<PathBuf>,
+}
+
+#[derive(Args, Debug)]
 pub struct CliArgs {
 	#[arg(long = "config", help = "Path to a TOML config file")]
 	pub config: Option<PathBuf>,
@@ -19,18 +145,110 @@ pub struct CliArgs {
 	#[arg(long = "words", value_delimiter = ',')]
 	pub words: Vec<String>,
 
-	#[arg(long = "word-file")]
+	#[arg(long = "word-file", help = "Word list file, or '-' to read from stdin")]
 	pub word_file: Option<PathBuf>,
 
 	#[arg(long = "weights-file")]
 	pub weights_file: Option<PathBuf>,
 
+	#[arg(
+		long = "text-file",
+		help = "Raw text corpus tokenized and counted into word weights"
+	)]
+	pub text_file: Option<PathBuf>,
+
+	#[arg(
+		long = "stop-words",
+		value_delimiter = ',',
+		help = "Extra words dropped from --text-file"
+	)]
+	pub stop_words: Vec<String>,
+
+	#[arg(
+		long = "no-default-stop-words",
+		default_value_t = false,
+		help = "Keep words from the built-in English stop word list"
+	)]
+	pub no_default_stop_words: bool,
+
+	#[arg(
+		long = "max-words",
+		help = "Keep only the N most frequent words from --text-file"
+	)]
+	pub max_words: Option<usize>,
+
+	#[arg(
+		long = "phrases",
+		num_args = 0..=1,
+		default_missing_value = "2",
+		value_parser = clap::value_parser!(u8).range(2..=3),
+		help = "Merge frequent collocations from --text-file into phrases of up to N words (default 2)"
+	)]
+	pub phrases: Option<u8>,
+
+	#[arg(
+		long = "phrase-min-count",
+		help = "Minimum occurrences before an n-gram can become a phrase"
+	)]
+	pub phrase_min_count: Option<usize>,
+
+	#[arg(
+		long = "phrase-min-pmi",
+		help = "Minimum pointwise mutual information (bits) for a phrase"
+	)]
+	pub phrase_min_pmi: Option<f32>,
+
+	#[arg(
+		long = "word-format",
+		value_enum,
+		help = "Word file format (default: from extension, or csv when columns are named)"
+	)]
+	pub word_format: Option<CliWordFormat>,
+
+	#[arg(
+		long = "word-column",
+		help = "CSV/TSV header name or 1-based index of the word column"
+	)]
+	pub word_column: Option<String>,
+
+	#[arg(
+		long = "weight-column",
+		help = "CSV/TSV header name or 1-based index of the weight column"
+	)]
+	pub weight_column: Option<String>,
+
+	#[arg(
+		long = "header",
+		help = "Treat the first CSV/TSV row as a header even when it looks like words"
+	)]
+	pub header: bool,
+
 	#[arg(long = "word-size-range", value_parser = parse_tuple)]
 	pub word_size_range: Option<(usize, usize)>,
 
+	#[arg(
+		long = "size-scaling",
+		value_enum,
+		help = "How word weights map to font sizes within --word-size-range"
+	)]
+	pub size_scaling: Option<CliSizeScaling>,
+
 	#[arg(long = "colors", value_delimiter = ',')]
 	pub word_colors: Option<Vec<String>>,
 
+	#[arg(
+		long = "color-by",
+		value_enum,
+		help = "How words pick their color from the palette"
+	)]
+	pub color_by: Option<CliColorStrategy>,
+
+	#[arg(
+		long = "color-image",
+		help = "Picture sampled by --color-by image (defaults to --mask-image)"
+	)]
+	pub color_image: Option<PathBuf>,
+
 	#[arg(
 		long = "palette",
 		value_enum,
@@ -50,21 +268,52 @@ pub struct CliArgs {
 	)]
 	pub palette_size: Option<usize>,
 
-	#[arg(long = "rotations", value_delimiter = ',')]
-	pub rotations: Option<Vec<u16>>,
+	#[arg(long = "rotations", value_delimiter = ',', allow_hyphen_values = true)]
+	pub rotations: Option<Vec<i16>>,
 
-	#[arg(short = 't', long = "text", required = true)]
-	pub shape_text: String,
+	#[arg(
+		long = "rotation-range",
+		value_parser = parse_rotation_range,
+		allow_hyphen_values = true,
+		help = "Pick a random angle in MIN,MAX degrees per word instead of --rotations"
+	)]
+	pub rotation_range: Option<(i16, i16)>,
 
-	#[arg(long = "text-size", value_parser = parse_shape_size)]
-	pub shape_size: Option<FontSizeSpec>,
+	#[command(flatten)]
+	pub shape: ShapeArgs,
 
 	#[arg(long = "algorithm", value_enum)]
 	pub algorithm: Option<CliAlgorithm>,
 
+	#[arg(
+		long = "collision",
+		value_enum,
+		help = "Collision test: word bounding boxes or inked glyph pixels"
+	)]
+	pub collision: Option<CliCollision>,
+
+	#[arg(
+		long = "placement",
+		value_enum,
+		help = "Word selection: weighted sampling or each word in descending weight order"
+	)]
+	pub placement: Option<CliPlacement>,
+
+	#[arg(
+		long = "repeats",
+		help = "How many times each word may be placed in ordered placement"
+	)]
+	pub repeats: Option<usize>,
+
 	#[arg(long = "font")]
 	pub font_path: Option<PathBuf>,
 
+	#[arg(
+		long = "fallback-font",
+		help = "Font tried, in the order given, for characters the main font lacks (repeatable)"
+	)]
+	pub fallback_fonts: Vec<PathBuf>,
+
 	#[arg(
 		long = "choose-system-font",
 		default_value_t = false,
@@ -81,19 +330,176 @@ pub struct CliArgs {
 	#[arg(long = "max-tries")]
 	pub max_tries: Option<usize>,
 
-	#[arg(long = "debug-mask-out")]
-	pub debug_mask_out: Option<PathBuf>,
+	#[arg(
+		long = "layout-out",
+		help = "Also write placements and stats as JSON to this path"
+	)]
+	pub layout_out: Option<PathBuf>,
 
 	#[arg(long = "no-progress", default_value_t = false)]
 	pub no_progress: bool,
 
-	#[arg(short = 'o', long = "output", required = true)]
+	#[arg(
+		short = 'o',
+		long = "output",
+		required = true,
+		help = "Output file; .png, .jpg/.jpeg and .webp render a bitmap, anything else SVG"
+	)]
+	pub output: Option<PathBuf>,
+
+	#[arg(
+		long = "svg-text",
+		value_enum,
+		help = "How SVG output draws words: <text> elements, glyph outline paths, or <text> with an embedded font subset"
+	)]
+	pub svg_text: Option<CliSvgText>,
+
+	#[arg(
+		long = "background",
+		help = "Background color for bitmap output (default: transparent)"
+	)]
+	pub background: Option<String>,
+
+	#[arg(long = "scale", help = "Pixel scale factor for bitmap output")]
+	pub scale: Option<f32>,
+
+	#[arg(short = 'v', long = "verbose", default_value_t = false)]
+	pub verbose: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct MaskArgs {
+	#[arg(long = "config", help = "Path to a TOML config file")]
+	pub config: Option<PathBuf>,
+
+	#[arg(long = "canvas-size", value_parser = parse_tuple)]
+	pub canvas_size: Option<(usize, usize)>,
+
+	#[arg(long = "canvas-margin")]
+	pub canvas_margin: Option<usize>,
+
+	#[command(flatten)]
+	pub shape: ShapeArgs,
+
+	#[arg(long = "font")]
+	pub font_path: Option<PathBuf>,
+
+	#[arg(
+		long = "fallback-font",
+		help = "Font tried, in the order given, for characters the main font lacks (repeatable)"
+	)]
+	pub fallback_fonts: Vec<PathBuf>,
+
+	#[arg(
+		long = "choose-system-font",
+		default_value_t = false,
+		help = "Prompt to choose a system font when embedded/default font is unavailable"
+	)]
+	pub choose_system_font: bool,
+
+	#[arg(
+		short = 'o',
+		long = "output",
+		help = "PNG file the shape mask is written to"
+	)]
 	pub output: PathBuf,
 
 	#[arg(short = 'v', long = "verbose", default_value_t = false)]
 	pub verbose: bool,
 }
 
+#[derive(Args, Debug)]
+pub struct PaletteArgs {
+	#[arg(value_enum, default_value = "auto", help = "Palette strategy")]
+	pub palette: PaletteKind,
+
+	#[arg(
+		long = "palette-base",
+		help = "Base color in #RRGGBB used by dynamic palettes"
+	)]
+	pub palette_base: Option<String>,
+
+	#[arg(
+		long = "palette-size",
+		help = "Number of colors generated by the palette"
+	)]
+	pub palette_size: Option<usize>,
+}
+
+#[derive(Args, Debug)]
+pub struct RenderArgs {
+	#[arg(help = "Layout JSON written by --layout-out")]
+	pub layout: PathBuf,
+
+	#[arg(
+		short = 'o',
+		long = "output",
+		help = "Output file; .png, .jpg/.jpeg and .webp render a bitmap, anything else SVG"
+	)]
+	pub output: PathBuf,
+
+	#[arg(long = "font")]
+	pub font_path: Option<PathBuf>,
+
+	#[arg(
+		long = "fallback-font",
+		help = "Font tried, in the order given, for characters the main font lacks (repeatable)"
+	)]
+	pub fallback_fonts: Vec<PathBuf>,
+
+	#[arg(
+		long = "choose-system-font",
+		default_value_t = false,
+		help = "Prompt to choose a system font when embedded/default font is unavailable"
+	)]
+	pub choose_system_font: bool,
+
+	#[arg(
+		long = "colors",
+		value_delimiter = ',',
+		help = "Recolor placements with these colors instead of the stored ones"
+	)]
+	pub word_colors: Option<Vec<String>>,
+
+	#[arg(
+		long = "palette",
+		value_enum,
+		help = "Recolor placements with a generated palette"
+	)]
+	pub palette: Option<PaletteKind>,
+
+	#[arg(
+		long = "palette-base",
+		help = "Base color in #RRGGBB used by dynamic palettes"
+	)]
+	pub palette_base: Option<String>,
+
+	#[arg(
+		long = "palette-size",
+		help = "Number of colors generated by the palette"
+	)]
+	pub palette_size: Option<usize>,
+
+	#[arg(
+		long = "svg-text",
+		value_enum,
+		help = "How SVG output draws words: <text> elements, glyph outline paths, or <text> with an embedded font subset"
+	)]
+	pub svg_text: Option<CliSvgText>,
+
+	#[arg(
+		long = "background",
+		help = "Background color for bitmap output (default: transparent)"
+	)]
+	pub background: Option<String>,
+
+	#[arg(long = "scale", help = "Pixel scale factor for bitmap output")]
+	pub scale: Option<f32>,
+
+	#[arg(short = 'v', long = "verbose", default_value_t = false)]
+	pub verbose: bool,
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum CliAlgorithm {
 	RandomBaseline,
@@ -130,6 +536,237 @@ impl From<CliAlgorithm> for AlgorithmKind {
 	}
 }
 
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliSizeScaling {
+	Uniform,
+	Linear,
+	Sqrt,
+	Log,
+	Rank,
+}
+
+impl CliSizeScaling {
+	pub fn parse_text(text: &str) -> Option<Self> {
+		match text.trim().to_ascii_lowercase().as_str() {
+			"uniform" | "none" => Some(Self::Uniform),
+			"linear" => Some(Self::Linear),
+			"sqrt" => Some(Self::Sqrt),
+			"log" => Some(Self::Log),
+			"rank" => Some(Self::Rank),
+			_ => None,
+		}
+	}
+}
+
+impl From<CliSizeScaling> for SizeScaling {
+	fn from(value: CliSizeScaling) -> Self {
+		match value {
+			CliSizeScaling::Uniform => SizeScaling::Uniform,
+			CliSizeScaling::Linear => SizeScaling::Linear,
+			CliSizeScaling::Sqrt => SizeScaling::Sqrt,
+			CliSizeScaling::Log => SizeScaling::Log,
+			CliSizeScaling::Rank => SizeScaling::Rank,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliColorStrategy {
+	Random,
+	Weight,
+	Word,
+	Group,
+	Image,
+}
+
+impl CliColorStrategy {
+	pub fn parse_text(text: &str) -> Option<Self> {
+		match text.trim().to_ascii_lowercase().as_str() {
+			"random" => Some(Self::Random),
+			"weight" | "by_weight" => Some(Self::Weight),
+			"word" | "by_word" => Some(Self::Word),
+			"group" | "by_group" => Some(Self::Group),
+			"image" => Some(Self::Image),
+			_ => None,
+		}
+	}
+
+	pub fn with_image(self, path: Option<PathBuf>) -> ColorStrategy {
+		match self {
+			CliColorStrategy::Random => ColorStrategy::Random,
+			CliColorStrategy::Weight => ColorStrategy::ByWeight,
+			CliColorStrategy::Word => ColorStrategy::ByWord,
+			CliColorStrategy::Group => ColorStrategy::ByGroup,
+			CliColorStrategy::Image => ColorStrategy::Image { path },
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliTextAlign {
+	Left,
+	Center,
+	Right,
+}
+
+impl CliTextAlign {
+	pub fn parse_text(text: &str) -> Option<Self> {
+		match text.trim().to_ascii_lowercase().as_str() {
+			"left" => Some(Self::Left),
+			"center" | "centre" => Some(Self::Center),
+			"right" => Some(Self::Right),
+			_ => None,
+		}
+	}
+}
+
+impl From<CliTextAlign> for TextAlign {
+	fn from(value: CliTextAlign) -> Self {
+		match value {
+			CliTextAlign::Left => TextAlign::Left,
+			CliTextAlign::Center => TextAlign::Center,
+			CliTextAlign::Right => TextAlign::Right,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliCollision {
+	Bbox,
+	Glyph,
+}
+
+impl CliCollision {
+	pub fn parse_text(text: &str) -> Option<Self> {
+		match text.trim().to_ascii_lowercase().as_str() {
+			"bbox" | "bounding-box" | "boundingbox" => Some(Self::Bbox),
+			"glyph" => Some(Self::Glyph),
+			_ => None,
+		}
+	}
+}
+
+impl From<CliCollision> for CollisionMode {
+	fn from(value: CliCollision) -> Self {
+		match value {
+			CliCollision::Bbox => CollisionMode::BoundingBox,
+			CliCollision::Glyph => CollisionMode::Glyph,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliPlacement {
+	Weighted,
+	Ordered,
+}
+
+impl CliPlacement {
+	pub fn parse_text(text: &str) -> Option<Self> {
+		match text.trim().to_ascii_lowercase().as_str() {
+			"weighted" => Some(Self::Weighted),
+			"ordered" | "once" => Some(Self::Ordered),
+			_ => None,
+		}
+	}
+
+	pub fn with_repeats(self, repeats: usize) -> PlacementMode {
+		match self {
+			CliPlacement::Weighted => PlacementMode::Weighted,
+			CliPlacement::Ordered => PlacementMode::Ordered { repeats },
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliSvgText {
+	Text,
+	Outlines,
+	Embed,
+}
+
+impl CliSvgText {
+	pub fn parse_text(text: &str) -> Option<Self> {
+		match text.trim().to_ascii_lowercase().as_str() {
+			"text" => Some(Self::Text),
+			"outlines" | "paths" => Some(Self::Outlines),
+			"embed" | "embedded-font" => Some(Self::Embed),
+			_ => None,
+		}
+	}
+}
+
+impl From<CliSvgText> for SvgTextMode {
+	fn from(value: CliSvgText) -> Self {
+		match value {
+			CliSvgText::Text => SvgTextMode::Text,
+			CliSvgText::Outlines => SvgTextMode::Outlines,
+			CliSvgText::Embed => SvgTextMode::EmbeddedFont,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliMaskChannel {
+	Auto,
+	Alpha,
+	Luminance,
+}
+
+impl From<CliMaskChannel> for MaskChannel {
+	fn from(value: CliMaskChannel) -> Self {
+		match value {
+			CliMaskChannel::Auto => MaskChannel::Auto,
+			CliMaskChannel::Alpha => MaskChannel::Alpha,
+			CliMaskChannel::Luminance => MaskChannel::Luminance,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CliWordFormat {
+	Lines,
+	Csv,
+	Tsv,
+}
+
+impl CliWordFormat {
+	fn infer(path: &Path, columns: WordColumns, content: &str) -> Self {
+		let extension = path
+			.extension()
+			.and_then(|ext| ext.to_str())
+			.map(|ext| ext.to_ascii_lowercase());
+		match extension.as_deref() {
+			Some("csv") => Self::Csv,
+			Some("tsv" | "tab") => Self::Tsv,
+			_ if !columns.is_set() => Self::Lines,
+			_ if content
+				.lines()
+				.next()
+				.is_some_and(|line| line.contains('\t')) =>
+			{
+				Self::Tsv
+			}
+			_ => Self::Csv,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum CliFillRule {
+	Nonzero,
+	Evenodd,
+}
+
+impl From<CliFillRule> for FillRule {
+	fn from(value: CliFillRule) -> Self {
+		match value {
+			CliFillRule::Nonzero => FillRule::NonZero,
+			CliFillRule::Evenodd => FillRule::EvenOdd,
+		}
+	}
+}
+
 #[derive(Debug, Clone, Copy, ValueEnum)]
 pub enum PaletteKind {
 	Auto,
@@ -158,47 +795,171 @@ impl PaletteKind {
 	}
 }
 
+pub fn shape_source(args: &ShapeArgs) -> Result<ShapeSource, GlyphWeaveError> {
+	if let Some(path) = &args.mask_image {
+		let mut config = ImageMaskConfig::new(path);
+		if let Some(threshold) = args.mask_threshold {
+			config.threshold = threshold;
+		}
+		if let Some(channel) = args.mask_channel {
+			config.channel = channel.into();
+		}
+		config.invert = args.mask_invert;
+		return Ok(ShapeSource::Image(config));
+	}
+
+	if let Some(path) = &args.mask_svg {
+		return Ok(ShapeSource::Svg(SvgMaskSource::File(path.clone())));
+	}
+
+	if let Some(data) = &args.mask_path {
+		return Ok(ShapeSource::Svg(SvgMaskSource::Path {
+			data: data.clone(),
+			fill_rule: args.mask_fill_rule.map(Into::into).unwrap_or_default(),
+		}));
+	}
+
+	if let Some(path) = &args.shape_layers {
+		let content = std::fs::read_to_string(path)?;
+		let layers: Vec<ShapeLayer> = serde_json::from_str(&content).map_err(|err| {
+			GlyphWeaveError::InvalidConfig(format!(
+				"invalid shape layers file {}: {err}",
+				path.display()
+			))
+		})?;
+		return Ok(ShapeSource::Layers(layers));
+	}
+
+	match &args.shape_text {
+		Some(text) => Ok(ShapeSource::Text(text.replace("\\n", "\n"))),
+		None => Err(GlyphWeaveError::InvalidConfig(
+			"no shape provided: use --text, --mask-image, --mask-svg, --mask-path or --shape-layers"
+				.to_string(),
+		)),
+	}
+}
+
 pub fn collect_words(args: &CliArgs) -> Result<Vec<WordEntry>, GlyphWeaveError> {
-	let mut table: BTreeMap<String, f32> = BTreeMap::new();
+	let mut table: BTreeMap<String, WordEntry> = BTreeMap::new();
 
 	for word in &args.words {
 		let normalized = word.trim();
 		if normalized.is_empty() {
 			continue;
 		}
-		*table.entry(normalized.to_string()).or_insert(0.0) += 1.0;
+		merge_word(&mut table, WordEntry::new(normalized, 1.0));
+	}
+
+	let stdin_sources = [&args.word_file, &args.weights_file, &args.text_file]
+		.into_iter()
+		.flatten()
+		.filter(|path| is_stdin(path))
+		.count();
+	if stdin_sources > 1 {
+		return Err(GlyphWeaveError::InvalidConfig(
+			"only one of --word-file, --weights-file and --text-file can read from stdin"
+				.to_string(),
+		));
 	}
 
+	let columns = WordColumns {
+		word: args.word_column.as_deref(),
+		weight: args.weight_column.as_deref(),
+		header: args.header,
+	};
+
 	if let Some(path) = &args.word_file {
-		for (word, weight) in parse_word_file(path)? {
-			*table.entry(word).or_insert(0.0) += weight;
+		for entry in parse_word_file(path, args.word_format, columns)? {
+			merge_word(&mut table, entry);
+		}
+	}
+
+	if let Some(path) = &args.text_file {
+		let options = TextOptions {
+			builtin_stop_words: !args.no_default_stop_words,
+			stop_words: args.stop_words.clone(),
+			max_words: args.max_words,
+			phrases: args.phrases.map(|max_len| {
+				let defaults = PhraseOptions::default();
+				PhraseOptions {
+					max_len: max_len as usize,
+					min_count: args.phrase_min_count.unwrap_or(defaults.min_count),
+					min_pmi: args.phrase_min_pmi.unwrap_or(defaults.min_pmi),
+				}
+			}),
+			..TextOptions::default()
+		};
+		let text = read_word_source(path)?;
+		for entry in words_from_text(&text, &options) {
+			merge_word(&mut table, entry);
 		}
 	}
 
 	if let Some(path) = &args.weights_file {
-		for (word, weight) in parse_word_file(path)? {
-			if let Some(existing) = table.get_mut(&word) {
-				*existing = weight;
+		for entry in parse_word_file(path, args.word_format, columns)? {
+			if let Some(existing) = table.get_mut(&entry.text) {
+				existing.weight = entry.weight;
 			}
 		}
 	}
 
 	let words = table
-		.into_iter()
-		.map(|(word, weight)| WordEntry::new(word, weight.max(0.0)))
+		.into_values()
+		.map(|mut entry| {
+			entry.weight = entry.weight.max(0.0);
+			entry
+		})
 		.collect::<Vec<_>>();
 
 	if words.is_empty() {
 		return Err(GlyphWeaveError::InvalidConfig(
-			"no words provided: use --words or --word-file".to_string(),
+			"no words provided: use --words, --word-file or --text-file".to_string(),
 		));
 	}
 
 	Ok(words)
 }
 
-pub fn parse_word_file(path: &Path) -> Result<Vec<(String, f32)>, GlyphWeaveError> {
-	let content = std::fs::read_to_string(path)?;
+// Repeated words add up their weights; style options given later win.
+fn merge_word(table: &mut BTreeMap<String, WordEntry>, entry: WordEntry) {
+	let Some(existing) = table.get_mut(&entry.text) else {
+		table.insert(entry.text.clone(), entry);
+		return;
+	};
+
+	existing.weight += entry.weight;
+	existing.color = entry.color.or(existing.color.take());
+	existing.rotations = entry.rotations.or(existing.rotations.take());
+	existing.font_size = entry.font_size.or(existing.font_size);
+	existing.link = entry.link.or(existing.link.take());
+	existing.class = entry.class.or(existing.class.take());
+	existing.group = entry.group.or(existing.group.take());
+}
+
+pub fn parse_word_file(
+	path: &Path,
+	format: Option<CliWordFormat>,
+	columns: WordColumns,
+) -> Result<Vec<WordEntry>, GlyphWeaveError> {
+	let content = read_word_source(path)?;
+	let source = if is_stdin(path) {
+		"<stdin>".to_string()
+	} else {
+		path.display().to_string()
+	};
+
+	let format = format.unwrap_or_else(|| CliWordFormat::infer(path, columns, &content));
+	match format {
+		CliWordFormat::Csv => return parse_word_table(&content, ',', columns, &source),
+		CliWordFormat::Tsv => return parse_word_table(&content, '\t', columns, &source),
+		CliWordFormat::Lines if columns.is_set() => {
+			return Err(GlyphWeaveError::InvalidConfig(
+				"--word-column/--weight-column/--header need a CSV or TSV word file".to_string(),
+			));
+		}
+		CliWordFormat::Lines => {}
+	}
+
 	let mut out = Vec::new();
 
 	for (index, raw_line) in content.lines().enumerate() {
@@ -207,14 +968,13 @@ pub fn parse_word_file(path: &Path) -> Result<Vec<(String, f32)>, GlyphWeaveErro
 			continue;
 		}
 
-		let Some((word, weight)) = parse_word_line(line) else {
-			return Err(GlyphWeaveError::InvalidConfig(format!(
-				"invalid word format in {} at line {}: '{line}'",
-				path.display(),
+		let entry = parse_word_line(line).map_err(|reason| {
+			GlyphWeaveError::InvalidConfig(format!(
+				"invalid word format in {source} at line {}: '{line}' ({reason})",
 				index + 1
-			)));
-		};
-		out.push((word, weight));
+			))
+		})?;
+		out.push(entry);
 	}
 
 	Ok(out)
@@ -233,20 +993,29 @@ pub fn parse_shape_size_text(input: &str) -> Result<FontSizeSpec, String> {
 	Ok(FontSizeSpec::Fixed(size))
 }
 
-fn parse_word_line(line: &str) -> Option<(String, f32)> {
-	let mut parts = line.splitn(2, ',');
-	let word = parts.next()?.trim();
+// `word[,weight][,key=value...]`, where keys are the per-word style options.
+fn parse_word_line(line: &str) -> Result<WordEntry, String> {
+	let mut parts = line.split(',');
+	let word = parts.next().unwrap_or_default().trim();
 	if word.is_empty() {
-		return None;
+		return Err("missing word".to_string());
 	}
 
-	let weight = if let Some(weight_raw) = parts.next() {
-		weight_raw.trim().parse::<f32>().ok()?
-	} else {
-		1.0
-	};
+	let mut entry = WordEntry::new(word, 1.0);
+	for (index, part) in parts.enumerate() {
+		match part.split_once('=') {
+			Some((key, value)) => apply_word_override(&mut entry, key, value)?,
+			None if index == 0 => {
+				entry.weight = part
+					.trim()
+					.parse::<f32>()
+					.map_err(|_| format!("invalid weight '{}'", part.trim()))?;
+			}
+			None => return Err(format!("unexpected field '{}'", part.trim())),
+		}
+	}
 
-	Some((word.to_string(), weight))
+	Ok(entry)
 }
 
 fn parse_tuple(input: &str) -> Result<(usize, usize), String> {
@@ -267,6 +1036,23 @@ fn parse_tuple(input: &str) -> Result<(usize, usize), String> {
 	Ok((first, second))
 }
 
+fn parse_rotation_range(input: &str) -> Result<(i16, i16), String> {
+	let Some((min, max)) = input.split_once(',') else {
+		return Err("value must use MIN,MAX format".to_string());
+	};
+
+	let min = min
+		.trim()
+		.parse::<i16>()
+		.map_err(|_| "invalid minimum angle".to_string())?;
+	let max = max
+		.trim()
+		.parse::<i16>()
+		.map_err(|_| "invalid maximum angle".to_string())?;
+
+	Ok((min, max))
+}
+
 fn parse_shape_size(input: &str) -> Result<FontSizeSpec, String> {
 	parse_shape_size_text(input)
 }
@@ -278,12 +1064,26 @@ mod tests {
 	#[test]
 	fn parse_word_line_supports_optional_weight() {
 		let entry = parse_word_line("hello,2.5").expect("line should parse");
-		assert_eq!(entry.0, "hello");
-		assert_eq!(entry.1, 2.5);
+		assert_eq!(entry.text, "hello");
+		assert_eq!(entry.weight, 2.5);
 
 		let entry = parse_word_line("world").expect("line should parse");
-		assert_eq!(entry.0, "world");
-		assert_eq!(entry.1, 1.0);
+		assert_eq!(entry.text, "world");
+		assert_eq!(entry.weight, 1.0);
+	}
+
+	#[test]
+	fn parse_word_line_reads_style_overrides() {
+		let entry = parse_word_line("acme,3,color=#E11D48,rotations=0|90,size=48,class=brand")
+			.expect("line should parse");
+		assert_eq!(entry.weight, 3.0);
+		assert_eq!(entry.color.as_deref(), Some("#E11D48"));
+		assert_eq!(entry.rotations.map(|r| r.len()), Some(2));
+		assert_eq!(entry.font_size, Some(48));
+		assert_eq!(entry.class.as_deref(), Some("brand"));
+
+		assert!(parse_word_line("acme,colour=red").is_err());
+		assert!(parse_word_line("acme,1,2").is_err());
 	}
 
 	#[test]
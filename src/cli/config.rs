@@ -1,4 +1,7 @@
-use crate::cli::args::{CliAlgorithm, PaletteKind};
+use crate::cli::args::{
+	CliAlgorithm, CliCollision, CliColorStrategy, CliPlacement, CliSizeScaling, CliSvgText,
+	CliTextAlign, PaletteKind,
+};
 use glyphweave::core::error::GlyphWeaveError;
 use serde::Deserialize;
 use std::path::{Path, PathBuf};
@@ -8,11 +11,22 @@ pub struct FileConfig {
 	pub canvas_size: Option<[usize; 2]>,
 	pub canvas_margin: Option<usize>,
 	pub word_size_range: Option<[usize; 2]>,
+	pub size_scaling: Option<String>,
 	pub colors: Option<Vec<String>>,
-	pub rotations: Option<Vec<u16>>,
+	pub color_by: Option<String>,
+	pub color_image: Option<PathBuf>,
+	pub rotations: Option<Vec<i16>>,
+	pub rotation_range: Option<[i16; 2]>,
 	pub text_size: Option<String>,
+	pub line_spacing: Option<f32>,
+	pub text_align: Option<String>,
+	pub shape_font: Option<PathBuf>,
 	pub algorithm: Option<String>,
+	pub collision: Option<String>,
+	pub placement: Option<String>,
+	pub repeats: Option<usize>,
 	pub font: Option<PathBuf>,
+	pub fallback_fonts: Option<Vec<PathBuf>>,
 	pub seed: Option<u64>,
 	pub ratio: Option<f32>,
 	pub max_tries: Option<usize>,
@@ -20,6 +34,9 @@ pub struct FileConfig {
 	pub palette: Option<String>,
 	pub palette_base: Option<String>,
 	pub palette_size: Option<usize>,
+	pub svg_text: Option<String>,
+	pub background: Option<String>,
+	pub scale: Option<f32>,
 }
 
 impl FileConfig {
@@ -33,21 +50,54 @@ impl FileConfig {
 		if other.word_size_range.is_some() {
 			self.word_size_range = other.word_size_range;
 		}
+		if other.size_scaling.is_some() {
+			self.size_scaling = other.size_scaling;
+		}
 		if other.colors.is_some() {
 			self.colors = other.colors;
 		}
+		if other.color_by.is_some() {
+			self.color_by = other.color_by;
+		}
+		if other.color_image.is_some() {
+			self.color_image = other.color_image;
+		}
 		if other.rotations.is_some() {
 			self.rotations = other.rotations;
 		}
+		if other.rotation_range.is_some() {
+			self.rotation_range = other.rotation_range;
+		}
 		if other.text_size.is_some() {
 			self.text_size = other.text_size;
 		}
+		if other.line_spacing.is_some() {
+			self.line_spacing = other.line_spacing;
+		}
+		if other.text_align.is_some() {
+			self.text_align = other.text_align;
+		}
+		if other.shape_font.is_some() {
+			self.shape_font = other.shape_font;
+		}
 		if other.algorithm.is_some() {
 			self.algorithm = other.algorithm;
 		}
+		if other.collision.is_some() {
+			self.collision = other.collision;
+		}
+		if other.placement.is_some() {
+			self.placement = other.placement;
+		}
+		if other.repeats.is_some() {
+			self.repeats = other.repeats;
+		}
 		if other.font.is_some() {
 			self.font = other.font;
 		}
+		if other.fallback_fonts.is_some() {
+			self.fallback_fonts = other.fallback_fonts;
+		}
 		if other.seed.is_some() {
 			self.seed = other.seed;
 		}
@@ -69,6 +119,15 @@ impl FileConfig {
 		if other.palette_size.is_some() {
 			self.palette_size = other.palette_size;
 		}
+		if other.svg_text.is_some() {
+			self.svg_text = other.svg_text;
+		}
+		if other.background.is_some() {
+			self.background = other.background;
+		}
+		if other.scale.is_some() {
+			self.scale = other.scale;
+		}
 	}
 
 	pub fn canvas_size_tuple(&self) -> Option<(usize, usize)> {
@@ -79,6 +138,10 @@ impl FileConfig {
 		self.word_size_range.map(|size| (size[0], size[1]))
 	}
 
+	pub fn rotation_range_tuple(&self) -> Option<(i16, i16)> {
+		self.rotation_range.map(|range| (range[0], range[1]))
+	}
+
 	pub fn algorithm_enum(&self) -> Result<Option<CliAlgorithm>, GlyphWeaveError> {
 		let Some(text) = self.algorithm.as_deref() else {
 			return Ok(None);
@@ -89,6 +152,66 @@ impl FileConfig {
 		})
 	}
 
+	pub fn size_scaling_enum(&self) -> Result<Option<CliSizeScaling>, GlyphWeaveError> {
+		let Some(text) = self.size_scaling.as_deref() else {
+			return Ok(None);
+		};
+
+		CliSizeScaling::parse_text(text).map(Some).ok_or_else(|| {
+			GlyphWeaveError::InvalidConfig(format!("invalid size scaling '{text}' in config"))
+		})
+	}
+
+	pub fn color_by_enum(&self) -> Result<Option<CliColorStrategy>, GlyphWeaveError> {
+		let Some(text) = self.color_by.as_deref() else {
+			return Ok(None);
+		};
+
+		CliColorStrategy::parse_text(text).map(Some).ok_or_else(|| {
+			GlyphWeaveError::InvalidConfig(format!("invalid color strategy '{text}' in config"))
+		})
+	}
+
+	pub fn text_align_enum(&self) -> Result<Option<CliTextAlign>, GlyphWeaveError> {
+		let Some(text) = self.text_align.as_deref() else {
+			return Ok(None);
+		};
+
+		CliTextAlign::parse_text(text).map(Some).ok_or_else(|| {
+			GlyphWeaveError::InvalidConfig(format!("invalid text align '{text}' in config"))
+		})
+	}
+
+	pub fn collision_enum(&self) -> Result<Option<CliCollision>, GlyphWeaveError> {
+		let Some(text) = self.collision.as_deref() else {
+			return Ok(None);
+		};
+
+		CliCollision::parse_text(text).map(Some).ok_or_else(|| {
+			GlyphWeaveError::InvalidConfig(format!("invalid collision mode '{text}' in config"))
+		})
+	}
+
+	pub fn placement_enum(&self) -> Result<Option<CliPlacement>, GlyphWeaveError> {
+		let Some(text) = self.placement.as_deref() else {
+			return Ok(None);
+		};
+
+		CliPlacement::parse_text(text).map(Some).ok_or_else(|| {
+			GlyphWeaveError::InvalidConfig(format!("invalid placement mode '{text}' in config"))
+		})
+	}
+
+	pub fn svg_text_enum(&self) -> Result<Option<CliSvgText>, GlyphWeaveError> {
+		let Some(text) = self.svg_text.as_deref() else {
+			return Ok(None);
+		};
+
+		CliSvgText::parse_text(text).map(Some).ok_or_else(|| {
+			GlyphWeaveError::InvalidConfig(format!("invalid svg text mode '{text}' in config"))
+		})
+	}
+
 	pub fn palette_enum(&self) -> Result<Option<PaletteKind>, GlyphWeaveError> {
 		let Some(text) = self.palette.as_deref() else {
 			return Ok(None);
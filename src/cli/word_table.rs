@@ -0,0 +1,349 @@
+use glyphweave::core::error::GlyphWeaveError;
+use glyphweave::core::model::WordEntry;
+use glyphweave::rotations_from_degrees;
+use std::io::Read;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordColumns<'a> {
+	pub word: Option<&'a str>,
+	pub weight: Option<&'a str>,
+	// Treat the first row as a header whatever it holds.
+	pub header: bool,
+}
+
+impl WordColumns<'_> {
+	pub fn is_set(&self) -> bool {
+		self.word.is_some() || self.weight.is_some() || self.header
+	}
+
+	// A column picked by header name rather than by 1-based index.
+	fn is_named(&self) -> bool {
+		[self.word, self.weight]
+			.into_iter()
+			.flatten()
+			.any(|name| name.trim().parse::<usize>().is_err())
+	}
+}
+
+pub fn is_stdin(path: &Path) -> bool {
+	path.as_os_str() == "-"
+}
+
+pub fn read_word_source(path: &Path) -> Result<String, GlyphWeaveError> {
+	if is_stdin(path) {
+		let mut content = String::new();
+		std::io::stdin().read_to_string(&mut content)?;
+		return Ok(content);
+	}
+
+	Ok(std::fs::read_to_string(path)?)
+}
+
+// Parses delimiter-separated records with RFC 4180 quoting. Each record carries
+// the 1-based line it starts on so errors can point at the input.
+pub fn parse_records(content: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, String> {
+	let mut records = Vec::new();
+	let mut fields = Vec::new();
+	let mut field = String::new();
+	let mut in_quotes = false;
+	let mut line = 1;
+	let mut record_line = 1;
+	let mut chars = content.chars().peekable();
+
+	while let Some(c) = chars.next() {
+		if in_quotes {
+			match c {
+				'"' if chars.peek() == Some(&'"') => {
+					chars.next();
+					field.push('"');
+				}
+				'"' => in_quotes = false,
+				_ => {
+					if c == '\n' {
+						line += 1;
+					}
+					field.push(c);
+				}
+			}
+			continue;
+		}
+
+		match c {
+			'"' if field.is_empty() => in_quotes = true,
+			'\r' if chars.peek() == Some(&'\n') => {}
+			'\n' => {
+				fields.push(std::mem::take(&mut field));
+				push_record(&mut records, record_line, std::mem::take(&mut fields));
+				line += 1;
+				record_line = line;
+			}
+			_ if c == delimiter => fields.push(std::mem::take(&mut field)),
+			_ => field.push(c),
+		}
+	}
+
+	if in_quotes {
+		return Err(format!(
+			"unterminated quoted field starting on line {record_line}"
+		));
+	}
+	if !field.is_empty() || !fields.is_empty() {
+		fields.push(field);
+		push_record(&mut records, record_line, fields);
+	}
+
+	Ok(records)
+}
+
+fn push_record(records: &mut Vec<(usize, Vec<String>)>, line: usize, fields: Vec<String>) {
+	if fields.iter().all(|field| field.trim().is_empty()) {
+		return;
+	}
+	records.push((line, fields));
+}
+
+// Per-word style keys accepted as `key=value` fields in line files and as
+// header names in CSV/TSV files.
+pub const OVERRIDE_KEYS: [&str; 6] = ["color", "rotations", "size", "link", "class", "group"];
+
+pub fn apply_word_override(entry: &mut WordEntry, key: &str, value: &str) -> Result<(), String> {
+	let value = value.trim();
+	match key.trim().to_ascii_lowercase().as_str() {
+		"color" => entry.color = Some(value.to_string()),
+		"rotations" | "rotation" => {
+			let degrees = value
+				.split(['|', ' '])
+				.filter(|part| !part.is_empty())
+				.map(|part| {
+					part.parse::<i16>()
+						.map_err(|_| format!("invalid rotation '{part}'"))
+				})
+				.collect::<Result<Vec<_>, _>>()?;
+			let rotations = rotations_from_degrees(&degrees).map_err(|err| err.to_string())?;
+			entry.rotations = Some(rotations);
+		}
+		"size" | "font_size" => {
+			let size = value
+				.parse::<usize>()
+				.ok()
+				.filter(|size| *size > 0)
+				.ok_or_else(|| format!("invalid size '{value}'"))?;
+			entry.font_size = Some(size);
+		}
+		"link" | "url" => entry.link = Some(value.to_string()),
+		"class" => entry.class = Some(value.to_string()),
+		"group" => entry.group = Some(value.to_string()),
+		other => return Err(format!("unknown word option '{other}'")),
+	}
+	Ok(())
+}
+
+pub fn parse_word_table(
+	content: &str,
+	delimiter: char,
+	columns: WordColumns,
+	source: &str,
+) -> Result<Vec<WordEntry>, GlyphWeaveError> {
+	let invalid = |reason: String| {
+		GlyphWeaveError::InvalidConfig(format!("invalid word table {source}: {reason}"))
+	};
+
+	let records = parse_records(content, delimiter).map_err(invalid)?;
+	let Some((_, first)) = records.first() else {
+		return Ok(Vec::new());
+	};
+
+	// `--header` and columns picked by name always need a header. Otherwise a
+	// first row whose weight cell is not a number is taken to be one; a
+	// single-column file gives nothing to tell a header from a word by.
+	let weight_cell = match columns.weight {
+		Some(name) => name
+			.trim()
+			.parse::<usize>()
+			.ok()
+			.and_then(|position| position.checked_sub(1)),
+		None if columns.word.is_none() => Some(1),
+		None => None,
+	};
+	let has_header = columns.header
+		|| columns.is_named()
+		|| (first.len() > 1
+			&& weight_cell
+				.and_then(|index| first.get(index))
+				.is_some_and(|cell| {
+					!cell.trim().is_empty() && cell.trim().parse::<f32>().is_err()
+				}));
+	let header = if has_header {
+		Some(first.as_slice())
+	} else {
+		None
+	};
+	let word_index = match columns.word {
+		Some(name) => column_index(header, name).map_err(invalid)?,
+		None => 0,
+	};
+	let weight_index = match columns.weight {
+		Some(name) => Some(column_index(header, name).map_err(invalid)?),
+		None if columns.word.is_none() => Some(1),
+		None => None,
+	};
+
+	let override_columns: Vec<(usize, &str)> = header
+		.into_iter()
+		.flatten()
+		.enumerate()
+		.filter(|(index, _)| *index != word_index && Some(*index) != weight_index)
+		.filter_map(|(index, name)| {
+			let name = name.trim();
+			OVERRIDE_KEYS
+				.iter()
+				.any(|key| key.eq_ignore_ascii_case(name))
+				.then_some((index, name))
+		})
+		.collect();
+
+	let mut out = Vec::new();
+	for (line, fields) in records.iter().skip(usize::from(has_header)) {
+		let word = fields.get(word_index).map(|cell| cell.trim()).unwrap_or("");
+		if word.is_empty() {
+			continue;
+		}
+
+		let weight = match weight_index
+			.and_then(|index| fields.get(index))
+			.map(|cell| cell.trim())
+		{
+			None | Some("") => 1.0,
+			Some(cell) => cell
+				.parse::<f32>()
+				.map_err(|_| invalid(format!("invalid weight '{cell}' on line {line}")))?,
+		};
+		let mut entry = WordEntry::new(word, weight);
+		for (index, key) in &override_columns {
+			if let Some(value) = fields.get(*index).filter(|cell| !cell.trim().is_empty()) {
+				apply_word_override(&mut entry, key, value)
+					.map_err(|reason| invalid(format!("{reason} on line {line}")))?;
+			}
+		}
+		out.push(entry);
+	}
+
+	Ok(out)
+}
+
+fn column_index(header: Option<&[String]>, name: &str) -> Result<usize, String> {
+	let name = name.trim();
+	if let Some(index) = header.and_then(|cells| {
+		cells
+			.iter()
+			.position(|cell| cell.trim().eq_ignore_ascii_case(name))
+	}) {
+		return Ok(index);
+	}
+
+	match name.parse::<usize>() {
+		Ok(position) if position >= 1 => Ok(position - 1),
+		_ => Err(format!("column '{name}' not found in header")),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn records_honour_quotes_and_escaped_quotes() {
+		let records = parse_records("term,count\n\"a, b\",2\n\"say \"\"hi\"\"\",3\r\n", ',')
+			.expect("records should parse");
+		assert_eq!(records.len(), 3);
+		assert_eq!(records[1].1, vec!["a, b", "2"]);
+		assert_eq!(records[2].1, vec!["say \"hi\"", "3"]);
+		assert!(parse_records("\"open,1", ',').is_err());
+	}
+
+	#[test]
+	fn word_table_selects_named_columns() {
+		let content = "id\tterm\tcount\n1\trust\t4\n2\t\"big data\"\t2.5\n";
+		let columns = WordColumns {
+			word: Some("term"),
+			weight: Some("count"),
+			..WordColumns::default()
+		};
+		let words = parse_word_table(content, '\t', columns, "stdin").expect("table should parse");
+		let summary: Vec<(&str, f32)> = words.iter().map(|w| (w.text.as_str(), w.weight)).collect();
+		assert_eq!(summary, vec![("rust", 4.0), ("big data", 2.5)]);
+
+		let missing = WordColumns {
+			word: Some("word"),
+			..WordColumns::default()
+		};
+		assert!(parse_word_table(content, '\t', missing, "stdin").is_err());
+	}
+
+	#[test]
+	fn header_names_match_case_insensitively() {
+		let columns = WordColumns {
+			word: Some("word"),
+			weight: Some("WEIGHT"),
+			..WordColumns::default()
+		};
+		let words = parse_word_table("Word,Weight\nrust,4\n", ',', columns, "words.csv")
+			.expect("table should parse");
+		assert_eq!(words.len(), 1);
+		assert_eq!((words[0].text.as_str(), words[0].weight), ("rust", 4.0));
+	}
+
+	#[test]
+	fn numeric_columns_do_not_imply_a_header() {
+		let columns = WordColumns {
+			word: Some("1"),
+			weight: Some("2"),
+			..WordColumns::default()
+		};
+		let words = parse_word_table("rust,5\nsvg,3\n", ',', columns, "words.csv")
+			.expect("table should parse");
+		let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+		assert_eq!(texts, vec!["rust", "svg"]);
+
+		let words = parse_word_table("term,count\nrust,5\n", ',', columns, "words.csv")
+			.expect("table should parse");
+		assert_eq!(words.len(), 1);
+	}
+
+	#[test]
+	fn single_column_header_needs_to_be_declared() {
+		let words = parse_word_table("text\nscore\n", ',', WordColumns::default(), "words.csv")
+			.expect("table should parse");
+		let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+		assert_eq!(texts, vec!["text", "score"]);
+
+		let header = WordColumns {
+			header: true,
+			..WordColumns::default()
+		};
+		let named = WordColumns {
+			word: Some("term"),
+			..WordColumns::default()
+		};
+		for columns in [header, named] {
+			let words = parse_word_table("Term\r\nrust\r\nsvg\r\n", ',', columns, "words.csv")
+				.expect("table should parse");
+			let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+			assert_eq!(texts, vec!["rust", "svg"]);
+		}
+	}
+
+	#[test]
+	fn word_table_reads_style_override_columns() {
+		let content =
+			"word,weight,color,rotations,link\nacme,3,#FF0000,0|90,https://acme.test\nplain,1,,,\n";
+		let words = parse_word_table(content, ',', WordColumns::default(), "words.csv")
+			.expect("table should parse");
+
+		assert_eq!(words[0].color.as_deref(), Some("#FF0000"));
+		assert_eq!(words[0].rotations.as_ref().map(Vec::len), Some(2));
+		assert_eq!(words[0].link.as_deref(), Some("https://acme.test"));
+		assert!(words[1].color.is_none() && words[1].link.is_none());
+	}
+}
@@ -1,10 +1,14 @@
 use crate::core::error::GlyphWeaveError;
-use fontdue::Font;
+use crate::font::{
+	FontChain, FontFace, font_family_name, load_default_embedded_font, load_font_from_file,
+};
+use serde::{Deserialize, Serialize};
 use std::ops::RangeInclusive;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CanvasConfig {
 	pub width: usize,
 	pub height: usize,
@@ -21,22 +25,179 @@ impl Default for CanvasConfig {
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FontSizeSpec {
 	Fixed(usize),
 	AutoFit,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MaskChannel {
+	#[default]
+	Auto,
+	Alpha,
+	Luminance,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageMaskConfig {
+	pub path: PathBuf,
+	#[serde(default = "default_mask_threshold")]
+	pub threshold: u8,
+	#[serde(default)]
+	pub channel: MaskChannel,
+	#[serde(default)]
+	pub invert: bool,
+}
+
+fn default_mask_threshold() -> u8 {
+	128
+}
+
+impl ImageMaskConfig {
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self {
+			path: path.into(),
+			threshold: default_mask_threshold(),
+			channel: MaskChannel::default(),
+			invert: false,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FillRule {
+	#[default]
+	NonZero,
+	EvenOdd,
+}
+
+impl FillRule {
+	pub fn svg_name(self) -> &'static str {
+		match self {
+			FillRule::NonZero => "nonzero",
+			FillRule::EvenOdd => "evenodd",
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgMaskSource {
+	File(PathBuf),
+	Document(String),
+	Path {
+		data: String,
+		#[serde(default)]
+		fill_rule: FillRule,
+	},
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeSource {
+	/// Shape text; `\n` starts a new line.
+	Text(String),
+	Image(ImageMaskConfig),
+	Svg(SvgMaskSource),
+	/// Shapes combined in order into one mask.
+	Layers(Vec<ShapeLayer>),
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TextAlign {
+	Left,
+	#[default]
+	Center,
+	Right,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ShapeConfig {
-	pub text: String,
+	pub source: ShapeSource,
 	pub font_size: FontSizeSpec,
+	/// Baseline-to-baseline distance of multi-line text, as a multiple of the
+	/// font's line height.
+	#[serde(default = "default_line_spacing")]
+	pub line_spacing: f32,
+	#[serde(default)]
+	pub align: TextAlign,
+	/// Face the shape text is drawn with, ahead of the word fonts that still
+	/// cover any characters it lacks. Layers inherit it. Unset uses the word
+	/// font, which is often too thin to give a roomy mask.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub font: Option<FontSource>,
 }
 
-#[derive(Debug, Clone)]
+fn default_line_spacing() -> f32 {
+	1.0
+}
+
+impl ShapeConfig {
+	/// Single-spaced, centered shape.
+	pub fn new(source: ShapeSource, font_size: FontSizeSpec) -> Self {
+		Self {
+			source,
+			font_size,
+			line_spacing: default_line_spacing(),
+			align: TextAlign::default(),
+			font: None,
+		}
+	}
+
+	pub fn with_font(mut self, font: FontSource) -> Self {
+		self.font = Some(font);
+		self
+	}
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShapeOp {
+	#[default]
+	Union,
+	Subtract,
+}
+
+/// Canvas area, in pixels, that a shape layer is fitted into.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ShapeRegion {
+	pub x: usize,
+	pub y: usize,
+	pub width: usize,
+	pub height: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShapeLayer {
+	pub shape: ShapeConfig,
+	/// Fills the whole canvas (inside its margin) when unset.
+	#[serde(default)]
+	pub region: Option<ShapeRegion>,
+	#[serde(default)]
+	pub op: ShapeOp,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WordEntry {
 	pub text: String,
 	pub weight: f32,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub color: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub rotations: Option<Vec<Rotation>>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub font_size: Option<usize>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub link: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub class: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub group: Option<String>,
 }
 
 impl WordEntry {
@@ -44,45 +205,203 @@ impl WordEntry {
 		Self {
 			text: text.into(),
 			weight,
+			color: None,
+			rotations: None,
+			font_size: None,
+			link: None,
+			class: None,
+			group: None,
+		}
+	}
+
+	pub fn with_color(mut self, color: impl Into<String>) -> Self {
+		self.color = Some(color.into());
+		self
+	}
+
+	pub fn with_rotations(mut self, rotations: Vec<Rotation>) -> Self {
+		self.rotations = Some(rotations);
+		self
+	}
+
+	pub fn with_font_size(mut self, font_size: usize) -> Self {
+		self.font_size = Some(font_size);
+		self
+	}
+
+	pub fn with_link(mut self, link: impl Into<String>) -> Self {
+		self.link = Some(link.into());
+		self
+	}
+
+	pub fn with_class(mut self, class: impl Into<String>) -> Self {
+		self.class = Some(class.into());
+		self
+	}
+
+	pub fn with_group(mut self, group: impl Into<String>) -> Self {
+		self.group = Some(group.into());
+		self
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TextOptions {
+	pub lowercase: bool,
+	pub builtin_stop_words: bool,
+	pub stop_words: Vec<String>,
+	pub max_words: Option<usize>,
+	pub phrases: Option<PhraseOptions>,
+}
+
+impl Default for TextOptions {
+	fn default() -> Self {
+		Self {
+			lowercase: true,
+			builtin_stop_words: true,
+			stop_words: Vec::new(),
+			max_words: None,
+			phrases: None,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PhraseOptions {
+	pub max_len: usize,
+	pub min_count: usize,
+	pub min_pmi: f32,
+}
+
+impl Default for PhraseOptions {
+	fn default() -> Self {
+		Self {
+			max_len: 2,
+			min_count: 3,
+			min_pmi: 3.0,
 		}
 	}
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
-pub enum Rotation {
-	Deg0,
-	Deg90,
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(from = "i16", into = "i16")]
+pub struct Rotation {
+	degrees: i16,
 }
 
 impl Rotation {
-	pub fn degrees(self) -> u16 {
-		match self {
-			Rotation::Deg0 => 0,
-			Rotation::Deg90 => 90,
+	pub const DEG0: Rotation = Rotation { degrees: 0 };
+	pub const DEG90: Rotation = Rotation { degrees: 90 };
+
+	#[deprecated(since = "0.4.0", note = "use `Rotation::DEG0`")]
+	#[allow(non_upper_case_globals)]
+	pub const Deg0: Rotation = Rotation::DEG0;
+	#[deprecated(since = "0.4.0", note = "use `Rotation::DEG90`")]
+	#[allow(non_upper_case_globals)]
+	pub const Deg90: Rotation = Rotation::DEG90;
+
+	pub fn from_degrees(degrees: i32) -> Self {
+		let mut normalized = degrees.rem_euclid(360);
+		if normalized > 180 {
+			normalized -= 360;
+		}
+		Self {
+			degrees: normalized as i16,
 		}
 	}
+
+	pub fn degrees(self) -> i16 {
+		self.degrees
+	}
+
+	pub fn radians(self) -> f32 {
+		(self.degrees as f32).to_radians()
+	}
+
+	pub fn is_axis_aligned(self) -> bool {
+		self.degrees % 90 == 0
+	}
 }
 
-#[derive(Debug, Clone)]
+impl From<i16> for Rotation {
+	fn from(degrees: i16) -> Self {
+		Self::from_degrees(degrees as i32)
+	}
+}
+
+impl From<Rotation> for i16 {
+	fn from(rotation: Rotation) -> Self {
+		rotation.degrees
+	}
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollisionMode {
+	#[default]
+	BoundingBox,
+	Glyph,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizeScaling {
+	#[default]
+	Uniform,
+	Linear,
+	Sqrt,
+	Log,
+	Rank,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorStrategy {
+	#[default]
+	Random,
+	ByWeight,
+	ByWord,
+	ByGroup,
+	/// Average color of a reference image under each word; without a path the
+	/// image shape's own picture is used.
+	Image {
+		#[serde(default)]
+		path: Option<PathBuf>,
+	},
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct StyleConfig {
 	pub font_size_range: RangeInclusive<usize>,
+	pub size_scaling: SizeScaling,
 	pub padding: usize,
 	pub colors: Vec<String>,
+	pub color_strategy: ColorStrategy,
 	pub rotations: Vec<Rotation>,
+	pub rotation_range: Option<RangeInclusive<i16>>,
+	pub collision: CollisionMode,
 }
 
 impl Default for StyleConfig {
 	fn default() -> Self {
 		Self {
 			font_size_range: 10..=30,
+			size_scaling: SizeScaling::default(),
 			padding: 0,
 			colors: vec!["#000000".to_string()],
-			rotations: vec![Rotation::Deg0],
+			color_strategy: ColorStrategy::default(),
+			rotations: vec![Rotation::DEG0],
+			rotation_range: None,
+			collision: CollisionMode::default(),
 		}
 	}
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AlgorithmKind {
 	RandomBaseline,
 	#[default]
@@ -92,10 +411,32 @@ pub enum AlgorithmKind {
 	SimulatedAnnealing,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementMode {
+	#[default]
+	Weighted,
+	Ordered {
+		repeats: usize,
+	},
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SvgTextMode {
+	#[default]
+	Text,
+	Outlines,
+	EmbeddedFont,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RenderOptions {
 	pub show_progress: bool,
+	// Library-only since the CLI flag gave way to `glyphweave mask`.
 	pub debug_mask_out: Option<PathBuf>,
+	pub svg_text: SvgTextMode,
 }
 
 impl Default for RenderOptions {
@@ -103,6 +444,23 @@ impl Default for RenderOptions {
 		Self {
 			show_progress: true,
 			debug_mask_out: None,
+			svg_text: SvgTextMode::Text,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RasterOptions {
+	pub scale: f32,
+	pub background: Option<String>,
+}
+
+impl Default for RasterOptions {
+	fn default() -> Self {
+		Self {
+			scale: 1.0,
+			background: None,
 		}
 	}
 }
@@ -114,14 +472,21 @@ pub struct CloudRequest {
 	pub words: Vec<WordEntry>,
 	pub style: StyleConfig,
 	pub algorithm: AlgorithmKind,
+	pub placement: PlacementMode,
 	pub ratio_threshold: f32,
 	pub max_try_count: usize,
 	pub seed: Option<u64>,
-	pub font: Arc<Font>,
+	pub font: Arc<FontFace>,
+	/// Faces tried in order for characters `font` has no glyph for.
+	pub fallback_fonts: Vec<Arc<FontFace>>,
 	pub render: RenderOptions,
 }
 
 impl CloudRequest {
+	pub fn font_chain(&self) -> FontChain {
+		FontChain::new(Arc::clone(&self.font)).with_fallbacks(self.fallback_fonts.iter().cloned())
+	}
+
 	pub fn validate(&self) -> Result<(), GlyphWeaveError> {
 		if self.canvas.width == 0 || self.canvas.height == 0 {
 			return Err(GlyphWeaveError::InvalidConfig(
@@ -143,11 +508,7 @@ impl CloudRequest {
 			));
 		}
 
-		if self.shape.text.trim().is_empty() {
-			return Err(GlyphWeaveError::InvalidConfig(
-				"shape text must not be empty".to_string(),
-			));
-		}
+		validate_shape(&self.shape)?;
 
 		if !(0.0..=1.0).contains(&self.ratio_threshold) {
 			return Err(GlyphWeaveError::InvalidConfig(
@@ -161,6 +522,12 @@ impl CloudRequest {
 			));
 		}
 
+		if self.placement == (PlacementMode::Ordered { repeats: 0 }) {
+			return Err(GlyphWeaveError::InvalidConfig(
+				"ordered placement repeats must be greater than 0".to_string(),
+			));
+		}
+
 		let min_size = *self.style.font_size_range.start();
 		let max_size = *self.style.font_size_range.end();
 		if min_size == 0 || min_size > max_size {
@@ -169,18 +536,51 @@ impl CloudRequest {
 			));
 		}
 
+		if let ColorStrategy::Image { path } = &self.style.color_strategy {
+			match path {
+				Some(path) if path.as_os_str().is_empty() => {
+					return Err(GlyphWeaveError::InvalidConfig(
+						"color image path must not be empty".to_string(),
+					));
+				}
+				None if !matches!(self.shape.source, ShapeSource::Image(_)) => {
+					return Err(GlyphWeaveError::InvalidConfig(
+						"image colors need a color image path or an image shape".to_string(),
+					));
+				}
+				_ => {}
+			}
+		}
+
 		if self.style.colors.is_empty() {
 			return Err(GlyphWeaveError::InvalidConfig(
 				"at least one color is required".to_string(),
 			));
 		}
 
+		if let Some(word) = self.words.iter().find(|word| word.font_size == Some(0)) {
+			return Err(GlyphWeaveError::InvalidConfig(format!(
+				"font size override for '{}' must be greater than 0",
+				word.text
+			)));
+		}
+
 		if self.style.rotations.is_empty() {
 			return Err(GlyphWeaveError::InvalidConfig(
 				"at least one rotation is required".to_string(),
 			));
 		}
 
+		if let Some(range) = &self.style.rotation_range
+			&& (range.start() > range.end()
+				|| range.start().unsigned_abs() > 360
+				|| range.end().unsigned_abs() > 360)
+		{
+			return Err(GlyphWeaveError::InvalidConfig(
+				"rotation_range must be ascending and within -360..=360 degrees".to_string(),
+			));
+		}
+
 		if self.words.iter().any(|w| w.text.trim().is_empty()) {
 			return Err(GlyphWeaveError::InvalidConfig(
 				"word list contains empty entries".to_string(),
@@ -191,17 +591,93 @@ impl CloudRequest {
 	}
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontSource {
+	#[default]
+	Embedded,
+	Path(PathBuf),
+}
+
+impl FontSource {
+	pub fn load(&self) -> Result<FontFace, GlyphWeaveError> {
+		match self {
+			FontSource::Embedded => load_default_embedded_font(),
+			FontSource::Path(path) => load_font_from_file(path),
+		}
+	}
+}
+
+// Serializable counterpart of `CloudRequest` that names its font instead of holding it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudRequestSpec {
+	#[serde(default)]
+	pub canvas: CanvasConfig,
+	pub shape: ShapeConfig,
+	pub words: Vec<WordEntry>,
+	#[serde(default)]
+	pub style: StyleConfig,
+	#[serde(default)]
+	pub algorithm: AlgorithmKind,
+	#[serde(default)]
+	pub placement: PlacementMode,
+	pub ratio_threshold: f32,
+	pub max_try_count: usize,
+	#[serde(default)]
+	pub seed: Option<u64>,
+	#[serde(default)]
+	pub font: FontSource,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub fallback_fonts: Vec<FontSource>,
+	#[serde(default)]
+	pub render: RenderOptions,
+}
+
+impl CloudRequestSpec {
+	pub fn into_request(self) -> Result<CloudRequest, GlyphWeaveError> {
+		let font = self.font.load()?;
+		let fallback_fonts = self
+			.fallback_fonts
+			.iter()
+			.map(|source| source.load().map(Arc::new))
+			.collect::<Result<_, _>>()?;
+		Ok(CloudRequest {
+			canvas: self.canvas,
+			shape: self.shape,
+			words: self.words,
+			style: self.style,
+			algorithm: self.algorithm,
+			placement: self.placement,
+			ratio_threshold: self.ratio_threshold,
+			max_try_count: self.max_try_count,
+			seed: self.seed,
+			font: Arc::new(font),
+			fallback_fonts,
+			render: self.render,
+		})
+	}
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloudPlacement {
 	pub word: String,
 	pub x: usize,
 	pub y: usize,
+	pub width: usize,
+	pub height: usize,
 	pub font_size: usize,
 	pub color: String,
+	// Set when `color` came from the word's own `color` override.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub fixed_color: bool,
 	pub rotation: Rotation,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub link: Option<String>,
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub class: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloudStats {
 	pub seed: u64,
 	pub shape_font_size: usize,
@@ -213,9 +689,159 @@ pub struct CloudStats {
 	pub elapsed_ms: u128,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloudLayout {
+	pub canvas: CanvasConfig,
+	pub font_family: String,
+	// Fonts the layout was measured with, so it can be drawn with the same faces.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub font: Option<FontSource>,
+	#[serde(default, skip_serializing_if = "Vec::is_empty")]
+	pub fallback_fonts: Vec<FontSource>,
+	pub placements: Vec<CloudPlacement>,
+	#[serde(default)]
+	pub unplaced_words: Vec<String>,
+	pub stats: CloudStats,
+}
+
+impl CloudLayout {
+	pub fn new(
+		canvas: &CanvasConfig,
+		font_family: impl Into<String>,
+		result: &CloudResult,
+	) -> Self {
+		Self {
+			canvas: canvas.clone(),
+			font_family: font_family.into(),
+			font: None,
+			fallback_fonts: Vec::new(),
+			placements: result.placements.clone(),
+			unplaced_words: result.unplaced_words.clone(),
+			stats: result.stats.clone(),
+		}
+	}
+
+	pub fn with_fonts(mut self, font: FontSource, fallback_fonts: Vec<FontSource>) -> Self {
+		self.font = Some(font);
+		self.fallback_fonts = fallback_fonts;
+		self
+	}
+
+	// The recorded fonts, or `None` for layouts saved without them.
+	pub fn load_fonts(&self) -> Result<Option<FontChain>, GlyphWeaveError> {
+		let Some(font) = &self.font else {
+			return Ok(None);
+		};
+		let fallbacks = self
+			.fallback_fonts
+			.iter()
+			.map(|source| source.load().map(Arc::new))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Some(
+			FontChain::new(Arc::new(font.load()?)).with_fallbacks(fallbacks),
+		))
+	}
+
+	// Placement boxes only fit the glyphs of the family they were measured with.
+	pub fn matches_font(&self, fonts: &FontChain) -> bool {
+		font_family_name(fonts.primary()) == self.font_family
+	}
+
+	// Every placement of a word gets the same color, in order of first
+	// appearance. Words with their own `color` override keep it.
+	pub fn recolor(&mut self, colors: &[String]) {
+		if colors.is_empty() {
+			return;
+		}
+		let mut words: Vec<String> = Vec::new();
+		for placement in &mut self.placements {
+			if placement.fixed_color {
+				continue;
+			}
+			let index = match words.iter().position(|word| *word == placement.word) {
+				Some(index) => index,
+				None => {
+					words.push(placement.word.clone());
+					words.len() - 1
+				}
+			};
+			placement.color = colors[index % colors.len()].clone();
+		}
+	}
+}
+
+fn validate_shape(shape: &ShapeConfig) -> Result<(), GlyphWeaveError> {
+	match &shape.source {
+		ShapeSource::Text(text) => {
+			if text.trim().is_empty() {
+				return Err(GlyphWeaveError::InvalidConfig(
+					"shape text must not be empty".to_string(),
+				));
+			}
+		}
+		ShapeSource::Image(image) => {
+			if image.path.as_os_str().is_empty() {
+				return Err(GlyphWeaveError::InvalidConfig(
+					"shape image path must not be empty".to_string(),
+				));
+			}
+		}
+		ShapeSource::Svg(SvgMaskSource::File(path)) => {
+			if path.as_os_str().is_empty() {
+				return Err(GlyphWeaveError::InvalidConfig(
+					"shape SVG path must not be empty".to_string(),
+				));
+			}
+		}
+		ShapeSource::Svg(SvgMaskSource::Document(data))
+		| ShapeSource::Svg(SvgMaskSource::Path { data, .. }) => {
+			if data.trim().is_empty() {
+				return Err(GlyphWeaveError::InvalidConfig(
+					"shape SVG data must not be empty".to_string(),
+				));
+			}
+		}
+		ShapeSource::Layers(layers) => {
+			if layers.is_empty() {
+				return Err(GlyphWeaveError::InvalidConfig(
+					"shape layers must not be empty".to_string(),
+				));
+			}
+			for layer in layers {
+				if layer
+					.region
+					.is_some_and(|region| region.width == 0 || region.height == 0)
+				{
+					return Err(GlyphWeaveError::InvalidConfig(
+						"shape layer region must have a non-zero size".to_string(),
+					));
+				}
+				validate_shape(&layer.shape)?;
+			}
+		}
+	}
+
+	if let Some(FontSource::Path(path)) = &shape.font
+		&& path.as_os_str().is_empty()
+	{
+		return Err(GlyphWeaveError::InvalidConfig(
+			"shape font path must not be empty".to_string(),
+		));
+	}
+
+	if !(shape.line_spacing.is_finite() && shape.line_spacing > 0.0) {
+		return Err(GlyphWeaveError::InvalidConfig(
+			"shape line spacing must be greater than 0".to_string(),
+		));
+	}
+
+	Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CloudResult {
 	pub svg: String,
 	pub placements: Vec<CloudPlacement>,
+	pub unplaced_words: Vec<String>,
 	pub stats: CloudStats,
 }
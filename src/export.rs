@@ -0,0 +1,25 @@
+use crate::core::error::GlyphWeaveError;
+use crate::core::model::CloudLayout;
+use std::path::Path;
+
+pub fn layout_to_json(layout: &CloudLayout) -> Result<String, GlyphWeaveError> {
+	serde_json::to_string_pretty(layout)
+		.map_err(|err| GlyphWeaveError::Generation(format!("failed to encode layout: {err}")))
+}
+
+pub fn save_layout_json(layout: &CloudLayout, path: &Path) -> Result<(), GlyphWeaveError> {
+	std::fs::write(path, layout_to_json(layout)?)?;
+	Ok(())
+}
+
+pub fn layout_from_json(json: &str) -> Result<CloudLayout, GlyphWeaveError> {
+	serde_json::from_str(json)
+		.map_err(|err| GlyphWeaveError::InvalidConfig(format!("invalid layout JSON: {err}")))
+}
+
+pub fn load_layout_json(path: &Path) -> Result<CloudLayout, GlyphWeaveError> {
+	let json = std::fs::read_to_string(path)?;
+	serde_json::from_str(&json).map_err(|err| {
+		GlyphWeaveError::InvalidConfig(format!("invalid layout file {}: {err}", path.display()))
+	})
+}
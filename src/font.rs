@@ -1,22 +1,194 @@
 use crate::core::error::GlyphWeaveError;
+use crate::woff::{decode_woff, is_woff};
 use fontdue::{Font, FontSettings};
 use std::collections::HashSet;
+use std::fmt;
+use std::io::Read;
+use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// A parsed font that keeps its source bytes for outline and embedding output.
+// WOFF and WOFF2 input is stored decoded; `index` picks the face of a
+// TrueType/OpenType collection.
+#[derive(Clone)]
+pub struct FontFace {
+	font: Font,
+	data: Arc<[u8]>,
+	index: u32,
+}
+
+impl FontFace {
+	pub fn from_bytes(data: impl Into<Arc<[u8]>>) -> Result<Self, String> {
+		Self::from_collection(data, 0)
+	}
+
+	pub fn from_collection(data: impl Into<Arc<[u8]>>, index: u32) -> Result<Self, String> {
+		let mut data = data.into();
+		if is_woff(&data) {
+			data = decode_woff(&data)?.into();
+		}
+		if let Some(count) = ttf_parser::fonts_in_collection(&data)
+			&& index >= count
+		{
+			return Err(format!(
+				"collection has {count} faces, index {index} is out of range"
+			));
+		}
+		let settings = FontSettings {
+			collection_index: index,
+			..FontSettings::default()
+		};
+		let font = Font::from_bytes(&data[..], settings)?;
+		Ok(Self { font, data, index })
+	}
+
+	pub fn font(&self) -> &Font {
+		&self.font
+	}
+
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	pub fn index(&self) -> u32 {
+		self.index
+	}
+
+	pub fn is_collection(&self) -> bool {
+		ttf_parser::fonts_in_collection(&self.data).is_some()
+	}
+
+	pub fn parse_outlines(&self) -> Result<ttf_parser::Face<'_>, GlyphWeaveError> {
+		ttf_parser::Face::parse(&self.data, self.index).map_err(|err| {
+			GlyphWeaveError::FontLoad(format!("failed to read font outlines: {err}"))
+		})
+	}
+}
+
+impl Deref for FontFace {
+	type Target = Font;
+
+	fn deref(&self) -> &Font {
+		&self.font
+	}
+}
+
+impl fmt::Debug for FontFace {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("FontFace")
+			.field("name", &self.font.name())
+			.field("index", &self.index)
+			.field("bytes", &self.data.len())
+			.finish()
+	}
+}
+
+// An ordered list of faces. Each character is drawn with the first face that
+// has a glyph for it; characters no face covers fall back to the primary.
+#[derive(Debug, Clone)]
+pub struct FontChain {
+	faces: Vec<Arc<FontFace>>,
+}
+
+impl FontChain {
+	pub fn new(primary: Arc<FontFace>) -> Self {
+		Self {
+			faces: vec![primary],
+		}
+	}
+
+	pub fn with_fallbacks(mut self, fallbacks: impl IntoIterator<Item = Arc<FontFace>>) -> Self {
+		self.faces.extend(fallbacks);
+		self
+	}
+
+	pub fn primary(&self) -> &FontFace {
+		&self.faces[0]
+	}
+
+	pub fn faces(&self) -> &[Arc<FontFace>] {
+		&self.faces
+	}
+
+	pub fn face(&self, index: usize) -> &FontFace {
+		&self.faces[index]
+	}
+
+	// Index of the face that draws `ch`.
+	pub fn resolve(&self, ch: char) -> usize {
+		if self.faces.len() == 1 {
+			return 0;
+		}
+		self.faces
+			.iter()
+			.position(|face| face.has_glyph(ch))
+			.unwrap_or(0)
+	}
+
+	// CSS `font-family` value listing every face in fallback order.
+	pub fn family_list(&self) -> String {
+		css_family_list(self.faces.iter().map(|face| face.as_ref()))
+	}
+
+	// Like `family_list`, but only names the faces that draw `text`.
+	pub fn family_list_for(&self, text: &str) -> String {
+		let mut used = vec![false; self.faces.len()];
+		for ch in text.chars() {
+			used[self.resolve(ch)] = true;
+		}
+		used[0] |= !used.contains(&true);
+		css_family_list(
+			self.faces
+				.iter()
+				.zip(used)
+				.filter_map(|(face, used)| used.then_some(face.as_ref())),
+		)
+	}
+}
+
+impl From<FontFace> for FontChain {
+	fn from(face: FontFace) -> Self {
+		Self::new(Arc::new(face))
+	}
+}
+
+impl From<Arc<FontFace>> for FontChain {
+	fn from(face: Arc<FontFace>) -> Self {
+		Self::new(face)
+	}
+}
 
 pub fn font_family_name(font: &Font) -> String {
 	font.name().unwrap_or("Unknown").to_string()
 }
 
-pub fn load_font_from_file<P: AsRef<Path>>(path: P) -> Result<Font, GlyphWeaveError> {
+fn css_family_list<'a>(faces: impl Iterator<Item = &'a FontFace>) -> String {
+	let mut families: Vec<String> = Vec::new();
+	for face in faces {
+		let family = css_family_name(&font_family_name(face));
+		if !families.contains(&family) {
+			families.push(family);
+		}
+	}
+	families.join(", ")
+}
+
+// Quotes a family name for CSS so names with spaces, digits or punctuation
+// parse as one family.
+pub fn css_family_name(name: &str) -> String {
+	format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// `path#N` selects face N of a `.ttc`/`.otc` collection.
+pub fn load_font_from_file<P: AsRef<Path>>(path: P) -> Result<FontFace, GlyphWeaveError> {
 	let path_ref = path.as_ref();
-	let font_data = std::fs::read(path_ref).map_err(|err| {
-		GlyphWeaveError::FontLoad(format!(
-			"failed to read font '{}': {err}",
-			path_ref.display()
-		))
+	let (file, index) = split_face_index(path_ref);
+	let font_data = std::fs::read(&file).map_err(|err| {
+		GlyphWeaveError::FontLoad(format!("failed to read font '{}': {err}", file.display()))
 	})?;
 
-	Font::from_bytes(font_data, FontSettings::default()).map_err(|err| {
+	FontFace::from_collection(font_data, index).map_err(|err| {
 		GlyphWeaveError::FontLoad(format!(
 			"failed to parse font '{}': {err}",
 			path_ref.display()
@@ -24,6 +196,19 @@ pub fn load_font_from_file<P: AsRef<Path>>(path: P) -> Result<Font, GlyphWeaveEr
 	})
 }
 
+// Splits a trailing `#N` face index off a font path. A file whose name really
+// ends in `#N` is left alone.
+fn split_face_index(path: &Path) -> (PathBuf, u32) {
+	let text = path.to_string_lossy();
+	if let Some((file, index)) = text.rsplit_once('#')
+		&& let Ok(index) = index.parse::<u32>()
+		&& !path.exists()
+	{
+		return (PathBuf::from(file), index);
+	}
+	(path.to_path_buf(), 0)
+}
+
 pub fn discover_system_font_candidates() -> Vec<PathBuf> {
 	let mut candidates = Vec::new();
 	let mut seen = HashSet::new();
@@ -44,14 +229,14 @@ pub fn discover_system_font_candidates() -> Vec<PathBuf> {
 	candidates
 }
 
-pub fn load_system_font() -> Result<(Font, PathBuf), GlyphWeaveError> {
+pub fn load_system_font() -> Result<(FontFace, PathBuf), GlyphWeaveError> {
 	let candidates = discover_system_font_candidates();
 	load_system_font_from_candidates(&candidates)
 }
 
 pub fn load_system_font_from_candidates(
 	candidates: &[PathBuf],
-) -> Result<(Font, PathBuf), GlyphWeaveError> {
+) -> Result<(FontFace, PathBuf), GlyphWeaveError> {
 	let mut parse_failures = 0usize;
 
 	for path in candidates {
@@ -75,31 +260,40 @@ pub fn load_system_font_from_candidates(
 }
 
 #[cfg(feature = "embedded_fonts")]
-pub fn load_default_embedded_font() -> Result<Font, GlyphWeaveError> {
-	Font::from_bytes(
-		crate::embedded_fonts::NOTO_SANS_SC_REGULAR,
-		FontSettings::default(),
-	)
-	.map_err(|err| GlyphWeaveError::FontLoad(format!("failed to parse embedded font: {err}")))
+pub fn load_default_embedded_font() -> Result<FontFace, GlyphWeaveError> {
+	FontFace::from_bytes(crate::embedded_fonts::NOTO_SANS_SC_REGULAR)
+		.map_err(|err| GlyphWeaveError::FontLoad(format!("failed to parse embedded font: {err}")))
 }
 
 #[cfg(not(feature = "embedded_fonts"))]
-pub fn load_default_embedded_font() -> Result<Font, GlyphWeaveError> {
+pub fn load_default_embedded_font() -> Result<FontFace, GlyphWeaveError> {
 	Err(GlyphWeaveError::FontLoad(
 		"no font provided and embedded_fonts feature is disabled".to_string(),
 	))
 }
 
+// Collections contribute one `path#N` candidate per face.
 fn push_font_candidate(path: PathBuf, out: &mut Vec<PathBuf>, seen: &mut HashSet<PathBuf>) {
-	if !is_supported_font_file(&path) {
+	if !is_supported_font_file(&path) || !path.exists() || !seen.insert(path.clone()) {
 		return;
 	}
 
-	if path.exists() && seen.insert(path.clone()) {
-		out.push(path);
+	match collection_face_count(&path) {
+		Some(count) => {
+			let file = path.to_string_lossy();
+			out.extend((0..count).map(|index| PathBuf::from(format!("{file}#{index}"))));
+		}
+		None => out.push(path),
 	}
 }
 
+fn collection_face_count(path: &Path) -> Option<u32> {
+	let mut header = [0u8; 12];
+	let mut file = std::fs::File::open(path).ok()?;
+	file.read_exact(&mut header).ok()?;
+	ttf_parser::fonts_in_collection(&header)
+}
+
 fn collect_font_files(
 	dir: &Path,
 	depth: usize,
@@ -130,7 +324,10 @@ fn is_supported_font_file(path: &Path) -> bool {
 	let Some(ext) = path.extension().and_then(|value| value.to_str()) else {
 		return false;
 	};
-	matches!(ext.to_ascii_lowercase().as_str(), "ttf" | "otf")
+	matches!(
+		ext.to_ascii_lowercase().as_str(),
+		"ttf" | "otf" | "ttc" | "otc" | "woff" | "woff2"
+	)
 }
 
 fn preferred_system_font_paths() -> Vec<PathBuf> {
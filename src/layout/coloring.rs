@@ -0,0 +1,207 @@
+use crate::core::model::{ColorStrategy, StyleConfig, WordEntry};
+use crate::layout::Footprint;
+use crate::layout::common::random_index;
+use crate::render::parse_color;
+use image::RgbaImage;
+use rand::RngCore;
+use std::borrow::Cow;
+
+#[derive(Debug, Clone)]
+pub struct ColorPicker {
+	strategy: ColorStrategy,
+	colors: Vec<String>,
+	// Palette indices from lightest to darkest, so heavier buckets get darker colors.
+	by_darkness: Vec<usize>,
+	distinct_weights: Vec<f32>,
+	groups: Vec<String>,
+	image: Option<RgbaImage>,
+}
+
+impl ColorPicker {
+	pub fn new(style: &StyleConfig, words: &[WordEntry]) -> Self {
+		let mut by_darkness: Vec<usize> = (0..style.colors.len()).collect();
+		by_darkness
+			.sort_by(|a, b| luminance(&style.colors[*b]).total_cmp(&luminance(&style.colors[*a])));
+
+		let mut distinct_weights: Vec<f32> = words.iter().map(|w| w.weight.max(0.0)).collect();
+		distinct_weights.sort_by(f32::total_cmp);
+		distinct_weights.dedup();
+
+		let mut groups: Vec<String> = words.iter().filter_map(|w| w.group.clone()).collect();
+		groups.sort_unstable();
+		groups.dedup();
+
+		Self {
+			strategy: style.color_strategy.clone(),
+			colors: style.colors.clone(),
+			by_darkness,
+			distinct_weights,
+			groups,
+			image: None,
+		}
+	}
+
+	/// Sets the canvas-sized picture sampled by `ColorStrategy::Image`.
+	pub fn with_image(mut self, image: RgbaImage) -> Self {
+		self.image = Some(image);
+		self
+	}
+
+	// A fixed per-word color skips the palette entirely. Only `Random` draws
+	// from the RNG (and `Image` when the word covers no visible pixel), so the
+	// other strategies give a word the same color on every repeat.
+	pub fn pick<'a>(
+		&'a self,
+		word: &'a WordEntry,
+		footprint: &Footprint,
+		x: usize,
+		y: usize,
+		rng: &mut dyn RngCore,
+	) -> Cow<'a, str> {
+		if let Some(color) = &word.color {
+			return Cow::Borrowed(color.as_str());
+		}
+
+		let count = self.colors.len();
+		let index = match self.strategy {
+			ColorStrategy::Image { .. } => match self.sample_image(footprint, x, y) {
+				Some(color) => return Cow::Owned(color),
+				None => random_index(rng, count),
+			},
+			ColorStrategy::Random => random_index(rng, count),
+			ColorStrategy::ByWeight => self.by_darkness[self.weight_bucket(word.weight, count)],
+			ColorStrategy::ByWord => stable_hash(&word.text) as usize % count,
+			ColorStrategy::ByGroup => match word.group.as_deref() {
+				Some(group) => {
+					self.groups
+						.binary_search_by(|candidate| candidate.as_str().cmp(group))
+						.unwrap_or(0) % count
+				}
+				None => stable_hash(&word.text) as usize % count,
+			},
+		};
+		Cow::Borrowed(self.colors[index].as_str())
+	}
+
+	// Alpha-weighted mean of the pixels under the footprint.
+	fn sample_image(&self, footprint: &Footprint, x: usize, y: usize) -> Option<String> {
+		let image = self.image.as_ref()?;
+		let mut sums = [0u64; 3];
+		let mut total_alpha = 0u64;
+		for (row, col) in footprint.cells(x, y) {
+			let Some(pixel) = image.get_pixel_checked(col as u32, row as u32) else {
+				continue;
+			};
+			let alpha = u64::from(pixel[3]);
+			for (sum, channel) in sums.iter_mut().zip(pixel.0) {
+				*sum += u64::from(channel) * alpha;
+			}
+			total_alpha += alpha;
+		}
+
+		if total_alpha == 0 {
+			return None;
+		}
+		let [r, g, b] = sums.map(|sum| (sum as f64 / total_alpha as f64).round() as u8);
+		Some(format!("#{r:02X}{g:02X}{b:02X}"))
+	}
+
+	// Buckets follow weight rank rather than raw weight, so a few very heavy
+	// words do not squeeze everyone else into the lightest bucket.
+	fn weight_bucket(&self, weight: f32, count: usize) -> usize {
+		let weight = weight.max(0.0);
+		let ranks = self.distinct_weights.len().saturating_sub(1);
+		let t = if ranks == 0 {
+			1.0
+		} else {
+			let rank = self
+				.distinct_weights
+				.partition_point(|candidate| *candidate < weight);
+			rank.min(ranks) as f32 / ranks as f32
+		};
+		(t * (count - 1) as f32).round() as usize
+	}
+}
+
+// Relative luminance in 0..=1; colors that do not parse sort as mid-grey.
+fn luminance(color: &str) -> f32 {
+	let Ok([r, g, b, _]) = parse_color(color) else {
+		return 0.5;
+	};
+	(0.2126 * r as f32 + 0.7152 * g as f32 + 0.0722 * b as f32) / 255.0
+}
+
+// FNV-1a, so word colors stay put across runs and Rust versions.
+fn stable_hash(text: &str) -> u64 {
+	text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+		(hash ^ u64::from(byte)).wrapping_mul(0x0000_0100_0000_01b3)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgba;
+	use rand::SeedableRng;
+	use rand::rngs::StdRng;
+
+	fn style(strategy: ColorStrategy) -> StyleConfig {
+		StyleConfig {
+			colors: vec![
+				"#111111".to_string(),
+				"#EEEEEE".to_string(),
+				"#888888".to_string(),
+			],
+			color_strategy: strategy,
+			..StyleConfig::default()
+		}
+	}
+
+	#[test]
+	fn strategies_give_meaningful_colors() {
+		let words = vec![
+			WordEntry::new("light", 1.0).with_group("b"),
+			WordEntry::new("middle", 2.0).with_group("a"),
+			WordEntry::new("heavy", 9.0).with_group("b"),
+		];
+		let mut rng = StdRng::seed_from_u64(7);
+		let cell = Footprint::solid(1, 1);
+
+		let by_weight = ColorPicker::new(&style(ColorStrategy::ByWeight), &words);
+		assert_eq!(by_weight.pick(&words[0], &cell, 0, 0, &mut rng), "#EEEEEE");
+		assert_eq!(by_weight.pick(&words[1], &cell, 0, 0, &mut rng), "#888888");
+		assert_eq!(by_weight.pick(&words[2], &cell, 0, 0, &mut rng), "#111111");
+
+		let by_word = ColorPicker::new(&style(ColorStrategy::ByWord), &words);
+		let first = by_word.pick(&words[1], &cell, 0, 0, &mut rng);
+		assert!((0..8).all(|_| by_word.pick(&words[1], &cell, 0, 0, &mut rng) == first));
+
+		let by_group = ColorPicker::new(&style(ColorStrategy::ByGroup), &words);
+		assert_eq!(by_group.pick(&words[1], &cell, 0, 0, &mut rng), "#111111");
+		assert_eq!(by_group.pick(&words[0], &cell, 0, 0, &mut rng), "#EEEEEE");
+		assert_eq!(by_group.pick(&words[2], &cell, 0, 0, &mut rng), "#EEEEEE");
+
+		let fixed = WordEntry::new("brand", 1.0).with_color("#E11D48");
+		assert_eq!(by_group.pick(&fixed, &cell, 0, 0, &mut rng), "#E11D48");
+	}
+
+	#[test]
+	fn image_colors_average_the_covered_pixels() {
+		let mut image = RgbaImage::new(4, 2);
+		image.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+		image.put_pixel(1, 0, Rgba([0, 0, 255, 255]));
+		image.put_pixel(2, 0, Rgba([0, 255, 0, 0]));
+		let style = StyleConfig {
+			color_strategy: ColorStrategy::Image { path: None },
+			..StyleConfig::default()
+		};
+		let word = WordEntry::new("photo", 1.0);
+		let picker = ColorPicker::new(&style, std::slice::from_ref(&word)).with_image(image);
+		let mut rng = StdRng::seed_from_u64(7);
+
+		let color = picker.pick(&word, &Footprint::solid(3, 1), 0, 0, &mut rng);
+		assert_eq!(color, "#800080");
+		let blank = picker.pick(&word, &Footprint::solid(1, 1), 3, 1, &mut rng);
+		assert_eq!(blank, "#000000");
+	}
+}
@@ -1,10 +1,9 @@
 use crate::core::model::{CloudPlacement, Rotation, StyleConfig, WordEntry};
-use crate::layout::LayoutRequest;
-use crate::mask::calculate_text_size;
-use fontdue::Font;
+use crate::layout::{Footprint, LayoutRequest, WordQueue};
 use indicatif::{ProgressBar, ProgressStyle};
 use ndarray::Array2;
 use rand::RngCore;
+use std::rc::Rc;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Rect {
@@ -16,9 +15,9 @@ pub struct Rect {
 
 #[derive(Debug, Clone)]
 pub struct PlacementCandidate {
-	pub word: String,
-	pub word_weight: f32,
+	pub word: WordEntry,
 	pub rect: Rect,
+	pub footprint: Rc<Footprint>,
 	pub font_size: usize,
 	pub rotation: Rotation,
 }
@@ -112,33 +111,41 @@ pub fn pick_weighted_word<'a>(
 	words.last()
 }
 
-pub fn pick_color<'a>(colors: &'a [String], rng: &mut dyn RngCore) -> &'a str {
-	let idx = random_index(rng, colors.len());
-	colors[idx].as_str()
-}
-
 pub fn random_unit_f32(rng: &mut dyn RngCore) -> f32 {
 	(rng.next_u64() as f64 / u64::MAX as f64) as f32
 }
 
-pub fn descending_font_sizes(style: &StyleConfig) -> impl Iterator<Item = usize> {
-	(*style.font_size_range.start()..=*style.font_size_range.end()).rev()
+pub fn rotation_candidates(
+	style: &StyleConfig,
+	word: &WordEntry,
+	rng: &mut dyn RngCore,
+) -> Vec<Rotation> {
+	if let Some(rotations) = word.rotations.as_ref().filter(|r| !r.is_empty()) {
+		return rotations.clone();
+	}
+
+	let Some(range) = &style.rotation_range else {
+		return style.rotations.clone();
+	};
+
+	let span = (*range.end() - *range.start()) as usize + 1;
+	let degrees = *range.start() as i32 + random_index(rng, span) as i32;
+	vec![Rotation::from_degrees(degrees)]
 }
 
 pub fn find_fit_at_position(
 	mask: &Array2<bool>,
 	x: usize,
 	y: usize,
-	word: &str,
-	style: &StyleConfig,
-	font: &Font,
-) -> Option<(usize, Rotation, Rect)> {
-	for size in descending_font_sizes(style) {
-		for rotation in &style.rotations {
-			let (w, h) = calculate_text_size(word, font, size, style.padding, *rotation);
-			let rect = Rect { x, y, w, h };
-			if is_area_available(mask, rect) {
-				return Some((size, *rotation, rect));
+	word: &WordEntry,
+	rotations: &[Rotation],
+	request: &LayoutRequest<'_>,
+) -> Option<(usize, Rotation, Rc<Footprint>)> {
+	for size in request.font_sizes(word) {
+		for rotation in rotations {
+			let footprint = request.footprint(&word.text, size, *rotation);
+			if footprint.fits(mask, x, y) {
+				return Some((size, *rotation, footprint));
 			}
 		}
 	}
@@ -150,6 +157,7 @@ pub fn sample_candidate(
 	mask: &Array2<bool>,
 	positions: &mut Vec<(usize, usize)>,
 	request: &LayoutRequest<'_>,
+	words: &WordQueue<'_>,
 	rng: &mut dyn RngCore,
 	max_trials: usize,
 ) -> Option<PlacementCandidate> {
@@ -165,14 +173,15 @@ pub fn sample_candidate(
 			continue;
 		}
 
-		let word = pick_weighted_word(request.words, rng)?;
-		if let Some((font_size, rotation, rect)) =
-			find_fit_at_position(mask, x, y, &word.text, request.style, request.font)
+		let word = words.pick(rng)?;
+		let rotations = rotation_candidates(request.style, word, rng);
+		if let Some((font_size, rotation, footprint)) =
+			find_fit_at_position(mask, x, y, word, &rotations, request)
 		{
 			return Some(PlacementCandidate {
-				word: word.text.clone(),
-				word_weight: word.weight.max(0.0),
-				rect,
+				word: word.clone(),
+				rect: footprint.rect(x, y),
+				footprint,
 				font_size,
 				rotation,
 			});
@@ -187,7 +196,9 @@ pub fn apply_candidate(
 	candidate: &PlacementCandidate,
 	color: &str,
 ) -> (CloudPlacement, usize) {
-	let consumed = occupy_area(mask, candidate.rect);
+	let consumed = candidate
+		.footprint
+		.occupy(mask, candidate.rect.x, candidate.rect.y);
 	let placed = placement(
 		&candidate.word,
 		candidate.rect,
@@ -202,24 +213,29 @@ pub fn candidate_quality(candidate: &PlacementCandidate, total_usable_area: usiz
 	if total_usable_area == 0 {
 		return 0.0;
 	}
-	let area_score = candidate.rect.area() as f32 / total_usable_area as f32;
-	area_score + candidate.word_weight * 0.01
+	let area_score = candidate.footprint.area() as f32 / total_usable_area as f32;
+	area_score + candidate.word.weight.max(0.0) * 0.01
 }
 
 pub fn placement(
-	word: &str,
+	word: &WordEntry,
 	rect: Rect,
 	font_size: usize,
 	color: &str,
 	rotation: Rotation,
 ) -> CloudPlacement {
 	CloudPlacement {
-		word: word.to_string(),
+		word: word.text.clone(),
 		x: rect.x,
 		y: rect.y,
+		width: rect.w,
+		height: rect.h,
 		font_size,
 		color: color.to_string(),
+		fixed_color: word.color.is_some(),
 		rotation,
+		link: word.link.clone(),
+		class: word.class.clone(),
 	}
 }
 
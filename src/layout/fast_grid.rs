@@ -1,14 +1,13 @@
 use crate::core::error::GlyphWeaveError;
-use crate::core::model::Rotation;
+use crate::core::model::{Rotation, WordEntry};
 use crate::layout::common::{
-	Rect, available_positions, create_progress_bar, descending_font_sizes, finish_progress,
-	intersects, occupy_area, pick_color, pick_weighted_word, placement, random_index, total_area,
-	update_progress,
+	Rect, available_positions, create_progress_bar, finish_progress, intersects, placement,
+	random_index, rotation_candidates, total_area, update_progress,
 };
-use crate::layout::{LayoutRequest, LayoutResult, LayoutStrategy};
-use crate::mask::calculate_text_size;
+use crate::layout::{Footprint, LayoutRequest, LayoutResult, LayoutStrategy};
 use ndarray::Array2;
 use rand::RngCore;
+use std::rc::Rc;
 
 const CANDIDATE_TRIALS: usize = 48;
 const INTEGRAL_REBUILD_INTERVAL: usize = 64;
@@ -33,6 +32,7 @@ impl LayoutStrategy for FastGridStrategy {
 		let mut integral = build_integral(&mask);
 		let mut pending_rects: Vec<Rect> = Vec::new();
 		let mut positions = available_positions(&mask);
+		let mut words = request.word_queue();
 		let mut placements = Vec::new();
 		let mut attempts = 0usize;
 		let mut used_area = 0usize;
@@ -45,10 +45,11 @@ impl LayoutStrategy for FastGridStrategy {
 			}
 
 			attempts += 1;
-			let Some(word_entry) = pick_weighted_word(request.words, rng) else {
+			let Some(word_entry) = words.pick(rng) else {
 				break;
 			};
 
+			let rotations = rotation_candidates(request.style, word_entry, rng);
 			let mut placed = false;
 			for _ in 0..CANDIDATE_TRIALS {
 				if positions.is_empty() {
@@ -66,22 +67,16 @@ impl LayoutStrategy for FastGridStrategy {
 					integral: &integral,
 					pending_rects: &pending_rects,
 					mask: &mask,
-					style: request.style,
-					font: request.font,
+					request,
 				};
-				if let Some((font_size, rotation, rect)) =
-					find_fit_with_integral(&context, x, y, &word_entry.text)
+				if let Some((font_size, rotation, footprint)) =
+					find_fit_with_integral(&context, x, y, word_entry, &rotations)
 				{
-					used_area += occupy_area(&mut mask, rect);
+					let rect = footprint.rect(x, y);
+					used_area += footprint.occupy(&mut mask, x, y);
 					pending_rects.push(rect);
-					let color = pick_color(&request.style.colors, rng);
-					placements.push(placement(
-						&word_entry.text,
-						rect,
-						font_size,
-						color,
-						rotation,
-					));
+					let color = request.color_picker.pick(word_entry, &footprint, x, y, rng);
+					placements.push(placement(word_entry, rect, font_size, &color, rotation));
 					placed = true;
 
 					if pending_rects.len() >= INTEGRAL_REBUILD_INTERVAL {
@@ -93,8 +88,13 @@ impl LayoutStrategy for FastGridStrategy {
 				}
 			}
 
-			if !placed && positions.len() < POOL_REFILL_THRESHOLD {
-				positions = available_positions(&mask);
+			if placed {
+				words.placed();
+			} else {
+				words.missed();
+				if positions.len() < POOL_REFILL_THRESHOLD {
+					positions = available_positions(&mask);
+				}
 			}
 
 			let ratio_progress = (used_area * 100) / total_usable_area;
@@ -116,23 +116,31 @@ struct FastFitContext<'a> {
 	integral: &'a Array2<u32>,
 	pending_rects: &'a [Rect],
 	mask: &'a Array2<bool>,
-	style: &'a crate::core::model::StyleConfig,
-	font: &'a fontdue::Font,
+	request: &'a LayoutRequest<'a>,
 }
 
 fn find_fit_with_integral(
 	context: &FastFitContext<'_>,
 	x: usize,
 	y: usize,
-	word: &str,
-) -> Option<(usize, Rotation, Rect)> {
-	for size in descending_font_sizes(context.style) {
-		for rotation in &context.style.rotations {
-			let (w, h) =
-				calculate_text_size(word, context.font, size, context.style.padding, *rotation);
-			let rect = Rect { x, y, w, h };
-			if is_area_available_fast(context.integral, context.pending_rects, context.mask, rect) {
-				return Some((size, *rotation, rect));
+	word: &WordEntry,
+	rotations: &[Rotation],
+) -> Option<(usize, Rotation, Rc<Footprint>)> {
+	for size in context.request.font_sizes(word) {
+		for rotation in rotations {
+			let footprint = context.request.footprint(&word.text, size, *rotation);
+			let fits = if footprint.is_solid() {
+				is_area_available_fast(
+					context.integral,
+					context.pending_rects,
+					context.mask,
+					footprint.rect(x, y),
+				)
+			} else {
+				footprint.fits(context.mask, x, y)
+			};
+			if fits {
+				return Some((size, *rotation, footprint));
 			}
 		}
 	}
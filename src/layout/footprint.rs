@@ -0,0 +1,143 @@
+use crate::core::model::{CollisionMode, Rotation, StyleConfig};
+use crate::font::FontChain;
+use crate::layout::common::{Rect, is_area_available, occupy_area};
+use crate::mask::{calculate_text_size, rasterize_text_coverage, rotate_mask};
+use ndarray::Array2;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+#[derive(Debug)]
+pub struct Footprint {
+	pub w: usize,
+	pub h: usize,
+	ink: Option<Vec<(usize, usize)>>,
+}
+
+impl Footprint {
+	pub fn solid(w: usize, h: usize) -> Self {
+		Self { w, h, ink: None }
+	}
+
+	pub fn from_coverage(coverage: &Array2<bool>) -> Self {
+		let (h, w) = coverage.dim();
+		let ink = coverage
+			.indexed_iter()
+			.filter_map(|((y, x), value)| if *value { Some((y, x)) } else { None })
+			.collect();
+		Self {
+			w,
+			h,
+			ink: Some(ink),
+		}
+	}
+
+	pub fn is_solid(&self) -> bool {
+		self.ink.is_none()
+	}
+
+	pub fn area(&self) -> usize {
+		match &self.ink {
+			Some(ink) => ink.len(),
+			None => self.w * self.h,
+		}
+	}
+
+	pub fn rect(&self, x: usize, y: usize) -> Rect {
+		Rect {
+			x,
+			y,
+			w: self.w,
+			h: self.h,
+		}
+	}
+
+	// Canvas cells (row, column) covered when the footprint sits at (x, y):
+	// the ink for glyph footprints, every cell of the box otherwise.
+	pub fn cells(&self, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+		let box_rows = if self.ink.is_none() { self.h } else { 0 };
+		let box_cells = (0..box_rows).flat_map(move |dy| (0..self.w).map(move |dx| (dy, dx)));
+		self.ink
+			.iter()
+			.flatten()
+			.copied()
+			.chain(box_cells)
+			.map(move |(dy, dx)| (y + dy, x + dx))
+	}
+
+	pub fn fits(&self, mask: &Array2<bool>, x: usize, y: usize) -> bool {
+		let rect = self.rect(x, y);
+		let Some(ink) = &self.ink else {
+			return is_area_available(mask, rect);
+		};
+
+		if rect.w == 0 || rect.h == 0 || ink.is_empty() {
+			return false;
+		}
+
+		if rect.x + rect.w > mask.ncols() || rect.y + rect.h > mask.nrows() {
+			return false;
+		}
+
+		ink.iter().all(|&(dy, dx)| mask[[y + dy, x + dx]])
+	}
+
+	pub fn occupy(&self, mask: &mut Array2<bool>, x: usize, y: usize) -> usize {
+		let Some(ink) = &self.ink else {
+			return occupy_area(mask, self.rect(x, y));
+		};
+
+		let mut consumed = 0usize;
+		for &(dy, dx) in ink {
+			let cell = &mut mask[[y + dy, x + dx]];
+			if *cell {
+				*cell = false;
+				consumed += 1;
+			}
+		}
+
+		consumed
+	}
+}
+
+#[derive(Debug, Default)]
+pub struct FootprintCache {
+	entries: RefCell<HashMap<(String, usize, Rotation), Rc<Footprint>>>,
+}
+
+impl FootprintCache {
+	pub fn get(
+		&self,
+		word: &str,
+		font_size: usize,
+		rotation: Rotation,
+		style: &StyleConfig,
+		fonts: &FontChain,
+	) -> Rc<Footprint> {
+		let key = (word.to_string(), font_size, rotation);
+		if let Some(footprint) = self.entries.borrow().get(&key) {
+			return Rc::clone(footprint);
+		}
+
+		let footprint = Rc::new(match style.collision {
+			CollisionMode::BoundingBox if rotation.is_axis_aligned() => {
+				let (w, h) = calculate_text_size(word, fonts, font_size, style.padding, rotation);
+				Footprint::solid(w, h)
+			}
+			CollisionMode::BoundingBox => {
+				let (w, h) =
+					calculate_text_size(word, fonts, font_size, style.padding, Rotation::DEG0);
+				Footprint::from_coverage(&rotate_mask(&Array2::from_elem((h, w), true), rotation))
+			}
+			CollisionMode::Glyph => Footprint::from_coverage(&rasterize_text_coverage(
+				word,
+				fonts,
+				font_size,
+				style.padding,
+				rotation,
+			)),
+		});
+		self.entries.borrow_mut().insert(key, Rc::clone(&footprint));
+		footprint
+	}
+}
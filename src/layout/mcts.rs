@@ -1,10 +1,9 @@
 use crate::core::error::GlyphWeaveError;
 use crate::layout::common::{
 	PlacementCandidate, apply_candidate, available_positions, candidate_quality,
-	create_progress_bar, finish_progress, pick_color, sample_candidate, total_area,
-	update_progress,
+	create_progress_bar, finish_progress, sample_candidate, total_area, update_progress,
 };
-use crate::layout::{LayoutRequest, LayoutResult, LayoutStrategy};
+use crate::layout::{LayoutRequest, LayoutResult, LayoutStrategy, WordQueue};
 use ndarray::Array2;
 use rand::RngCore;
 
@@ -40,6 +39,7 @@ impl LayoutStrategy for MctsStrategy {
 		}
 
 		let mut positions = available_positions(&mask);
+		let mut words = request.word_queue();
 		let mut placements = Vec::new();
 		let mut attempts = 0usize;
 		let mut used_area = 0usize;
@@ -60,8 +60,12 @@ impl LayoutStrategy for MctsStrategy {
 				break;
 			}
 
-			let mut children = sample_children(&mask, &mut positions, request, rng);
+			let mut children = sample_children(&mask, &mut positions, request, &words, rng);
 			if children.is_empty() {
+				if words.is_exhausted() {
+					break;
+				}
+				words.missed();
 				continue;
 			}
 
@@ -71,6 +75,7 @@ impl LayoutStrategy for MctsStrategy {
 					&mask,
 					&children[selected].candidate,
 					request,
+					&words,
 					total_usable_area,
 					rng,
 				);
@@ -81,10 +86,17 @@ impl LayoutStrategy for MctsStrategy {
 
 			let best = best_child_index(&children);
 			let best_candidate = children.swap_remove(best).candidate;
-			let color = pick_color(&request.style.colors, rng);
-			let (placed, consumed) = apply_candidate(&mut mask, &best_candidate, color);
+			let color = request.color_picker.pick(
+				&best_candidate.word,
+				&best_candidate.footprint,
+				best_candidate.rect.x,
+				best_candidate.rect.y,
+				rng,
+			);
+			let (placed, consumed) = apply_candidate(&mut mask, &best_candidate, &color);
 			used_area += consumed;
 			placements.push(placed);
+			words.placed();
 
 			let ratio_progress = (used_area * 100) / total_usable_area;
 			let try_progress = (attempts * 100) / request.max_try_count;
@@ -105,12 +117,15 @@ fn sample_children(
 	mask: &Array2<bool>,
 	positions: &mut Vec<(usize, usize)>,
 	request: &LayoutRequest<'_>,
+	words: &WordQueue<'_>,
 	rng: &mut dyn RngCore,
 ) -> Vec<ChildNode> {
 	let mut children = Vec::new();
 
 	for _ in 0..CHILDREN_PER_STEP {
-		if let Some(candidate) = sample_candidate(mask, positions, request, rng, CANDIDATE_TRIALS) {
+		if let Some(candidate) =
+			sample_candidate(mask, positions, request, words, rng, CANDIDATE_TRIALS)
+		{
 			children.push(ChildNode {
 				candidate,
 				visits: 0,
@@ -172,13 +187,18 @@ fn rollout_reward(
 	mask: &Array2<bool>,
 	first: &PlacementCandidate,
 	request: &LayoutRequest<'_>,
+	words: &WordQueue<'_>,
 	total_usable_area: usize,
 	rng: &mut dyn RngCore,
 ) -> f32 {
 	let mut local_mask = mask.clone();
+	let mut local_words = words.clone();
+	local_words.placed();
 	let mut reward = 0.0f32;
 
-	let first_consumed = crate::layout::common::occupy_area(&mut local_mask, first.rect);
+	let first_consumed = first
+		.footprint
+		.occupy(&mut local_mask, first.rect.x, first.rect.y);
 	reward += first_consumed as f32 / total_usable_area as f32;
 	reward += candidate_quality(first, total_usable_area);
 
@@ -192,14 +212,19 @@ fn rollout_reward(
 			&local_mask,
 			&mut positions,
 			request,
+			&local_words,
 			rng,
 			ROLLOUT_CANDIDATE_TRIALS,
 		) else {
 			break;
 		};
 
-		let consumed = crate::layout::common::occupy_area(&mut local_mask, candidate.rect);
+		let consumed =
+			candidate
+				.footprint
+				.occupy(&mut local_mask, candidate.rect.x, candidate.rect.y);
 		reward += consumed as f32 / total_usable_area as f32;
+		local_words.placed();
 	}
 
 	reward
@@ -1,31 +1,66 @@
+mod coloring;
 mod common;
 mod fast_grid;
+mod footprint;
 mod mcts;
 mod random_baseline;
 mod simulated_annealing;
+mod sizing;
 mod spiral_greedy;
+mod word_queue;
 
 use crate::core::error::GlyphWeaveError;
-use crate::core::model::{AlgorithmKind, CloudPlacement, StyleConfig, WordEntry};
-use fontdue::Font;
+use crate::core::model::{
+	AlgorithmKind, CloudPlacement, PlacementMode, Rotation, StyleConfig, WordEntry,
+};
+use crate::font::FontChain;
 use ndarray::Array2;
 use rand::RngCore;
+use std::rc::Rc;
 
+pub use coloring::ColorPicker;
 pub use fast_grid::FastGridStrategy;
+pub use footprint::{Footprint, FootprintCache};
 pub use mcts::MctsStrategy;
 pub use random_baseline::RandomBaselineStrategy;
 pub use simulated_annealing::SimulatedAnnealingStrategy;
+pub use sizing::SizeScale;
 pub use spiral_greedy::SpiralGreedyStrategy;
+pub use word_queue::WordQueue;
 
 #[derive(Debug)]
 pub struct LayoutRequest<'a> {
 	pub mask: &'a Array2<bool>,
 	pub words: &'a [WordEntry],
 	pub style: &'a StyleConfig,
-	pub font: &'a Font,
+	pub fonts: &'a FontChain,
 	pub ratio_threshold: f32,
 	pub max_try_count: usize,
+	pub placement: PlacementMode,
 	pub show_progress: bool,
+	pub footprints: FootprintCache,
+	pub size_scale: SizeScale,
+	pub color_picker: ColorPicker,
+}
+
+impl<'a> LayoutRequest<'a> {
+	pub fn word_queue(&self) -> WordQueue<'a> {
+		WordQueue::new(self.words, self.placement)
+	}
+
+	pub fn footprint(&self, word: &str, font_size: usize, rotation: Rotation) -> Rc<Footprint> {
+		self.footprints
+			.get(word, font_size, rotation, self.style, self.fonts)
+	}
+
+	// A per-word size override replaces the weight-derived starting size; words
+	// still shrink towards the bottom of the range when they do not fit.
+	pub fn font_sizes(&self, word: &WordEntry) -> impl Iterator<Item = usize> + use<> {
+		let target = word
+			.font_size
+			.unwrap_or_else(|| self.size_scale.target_size(word.weight));
+		((*self.style.font_size_range.start()).min(target)..=target).rev()
+	}
 }
 
 #[derive(Debug)]
@@ -1,7 +1,7 @@
 use crate::core::error::GlyphWeaveError;
 use crate::layout::common::{
-	available_positions, create_progress_bar, find_fit_at_position, finish_progress, occupy_area,
-	pick_color, pick_weighted_word, placement, random_index, total_area, update_progress,
+	available_positions, create_progress_bar, find_fit_at_position, finish_progress, placement,
+	random_index, rotation_candidates, total_area, update_progress,
 };
 use crate::layout::{LayoutRequest, LayoutResult, LayoutStrategy};
 use rand::RngCore;
@@ -22,6 +22,7 @@ impl LayoutStrategy for RandomBaselineStrategy {
 			));
 		}
 
+		let mut words = request.word_queue();
 		let mut placements = Vec::new();
 		let mut attempts = 0usize;
 		let mut used_area = 0usize;
@@ -41,22 +42,26 @@ impl LayoutStrategy for RandomBaselineStrategy {
 			}
 
 			let (y, x) = positions[random_index(rng, positions.len())];
-			let Some(word_entry) = pick_weighted_word(request.words, rng) else {
+			let Some(word_entry) = words.pick(rng) else {
 				break;
 			};
 
-			if let Some((font_size, rotation, rect)) =
-				find_fit_at_position(&mask, x, y, &word_entry.text, request.style, request.font)
+			let rotations = rotation_candidates(request.style, word_entry, rng);
+			if let Some((font_size, rotation, footprint)) =
+				find_fit_at_position(&mask, x, y, word_entry, &rotations, request)
 			{
-				used_area += occupy_area(&mut mask, rect);
-				let color = pick_color(&request.style.colors, rng);
+				used_area += footprint.occupy(&mut mask, x, y);
+				let color = request.color_picker.pick(word_entry, &footprint, x, y, rng);
 				placements.push(placement(
-					&word_entry.text,
-					rect,
+					word_entry,
+					footprint.rect(x, y),
 					font_size,
-					color,
+					&color,
 					rotation,
 				));
+				words.placed();
+			} else {
+				words.missed();
 			}
 
 			let ratio_progress = (used_area * 100) / total_usable_area;
@@ -1,7 +1,7 @@
 use crate::core::error::GlyphWeaveError;
 use crate::layout::common::{
 	apply_candidate, available_positions, candidate_quality, create_progress_bar, finish_progress,
-	pick_color, random_unit_f32, sample_candidate, total_area, update_progress,
+	random_unit_f32, sample_candidate, total_area, update_progress,
 };
 use crate::layout::{LayoutRequest, LayoutResult, LayoutStrategy};
 use rand::RngCore;
@@ -29,6 +29,7 @@ impl LayoutStrategy for SimulatedAnnealingStrategy {
 		}
 
 		let mut positions = available_positions(&mask);
+		let mut words = request.word_queue();
 		let mut placements = Vec::new();
 		let mut attempts = 0usize;
 		let mut used_area = 0usize;
@@ -51,9 +52,18 @@ impl LayoutStrategy for SimulatedAnnealingStrategy {
 				break;
 			}
 
-			let Some(candidate) =
-				sample_candidate(&mask, &mut positions, request, rng, CANDIDATE_TRIALS)
-			else {
+			let Some(candidate) = sample_candidate(
+				&mask,
+				&mut positions,
+				request,
+				&words,
+				rng,
+				CANDIDATE_TRIALS,
+			) else {
+				if words.is_exhausted() {
+					break;
+				}
+				words.missed();
 				temperature = (temperature * COOLING_RATE).max(MIN_TEMPERATURE);
 				continue;
 			};
@@ -68,11 +78,18 @@ impl LayoutStrategy for SimulatedAnnealingStrategy {
 			};
 
 			if accepted {
-				let color = pick_color(&request.style.colors, rng);
-				let (placed, consumed) = apply_candidate(&mut mask, &candidate, color);
+				let color = request.color_picker.pick(
+					&candidate.word,
+					&candidate.footprint,
+					candidate.rect.x,
+					candidate.rect.y,
+					rng,
+				);
+				let (placed, consumed) = apply_candidate(&mut mask, &candidate, &color);
 				used_area += consumed;
 				current_score = candidate_score;
 				placements.push(placed);
+				words.placed();
 			}
 
 			temperature = (temperature * COOLING_RATE).max(MIN_TEMPERATURE);
@@ -0,0 +1,103 @@
+use crate::core::model::{SizeScaling, StyleConfig, WordEntry};
+
+#[derive(Debug, Clone)]
+pub struct SizeScale {
+	scaling: SizeScaling,
+	min_size: usize,
+	max_size: usize,
+	min_weight: f32,
+	max_weight: f32,
+	distinct_weights: Vec<f32>,
+}
+
+impl SizeScale {
+	pub fn new(style: &StyleConfig, words: &[WordEntry]) -> Self {
+		let mut distinct_weights: Vec<f32> = words.iter().map(|w| w.weight.max(0.0)).collect();
+		distinct_weights.sort_by(f32::total_cmp);
+		distinct_weights.dedup();
+
+		Self {
+			scaling: style.size_scaling,
+			min_size: *style.font_size_range.start(),
+			max_size: *style.font_size_range.end(),
+			min_weight: distinct_weights.first().copied().unwrap_or(0.0),
+			max_weight: distinct_weights.last().copied().unwrap_or(0.0),
+			distinct_weights,
+		}
+	}
+
+	pub fn target_size(&self, weight: f32) -> usize {
+		let weight = weight.max(0.0);
+		let t = match self.scaling {
+			SizeScaling::Uniform => 1.0,
+			SizeScaling::Linear => normalize(weight, self.min_weight, self.max_weight),
+			SizeScaling::Sqrt => normalize(
+				weight.sqrt(),
+				self.min_weight.sqrt(),
+				self.max_weight.sqrt(),
+			),
+			SizeScaling::Log => normalize(
+				weight.ln_1p(),
+				self.min_weight.ln_1p(),
+				self.max_weight.ln_1p(),
+			),
+			SizeScaling::Rank => {
+				let rank = self
+					.distinct_weights
+					.partition_point(|candidate| *candidate < weight);
+				normalize(
+					rank as f32,
+					0.0,
+					self.distinct_weights.len().saturating_sub(1) as f32,
+				)
+			}
+		};
+
+		let span = (self.max_size - self.min_size) as f32;
+		self.min_size + (t.clamp(0.0, 1.0) * span).round() as usize
+	}
+}
+
+fn normalize(value: f32, min: f32, max: f32) -> f32 {
+	if max - min <= f32::EPSILON {
+		return 1.0;
+	}
+	(value - min) / (max - min)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn style(scaling: SizeScaling) -> StyleConfig {
+		StyleConfig {
+			font_size_range: 10..=30,
+			size_scaling: scaling,
+			..StyleConfig::default()
+		}
+	}
+
+	#[test]
+	fn weights_map_into_font_size_range() {
+		let words = vec![
+			WordEntry::new("a", 1.0),
+			WordEntry::new("b", 4.0),
+			WordEntry::new("c", 100.0),
+		];
+
+		let uniform = SizeScale::new(&style(SizeScaling::Uniform), &words);
+		assert_eq!(uniform.target_size(1.0), 30);
+
+		let linear = SizeScale::new(&style(SizeScaling::Linear), &words);
+		assert_eq!(linear.target_size(1.0), 10);
+		assert_eq!(linear.target_size(100.0), 30);
+		assert_eq!(linear.target_size(4.0), 11);
+
+		let sqrt = SizeScale::new(&style(SizeScaling::Sqrt), &words);
+		assert_eq!(sqrt.target_size(4.0), 12);
+
+		let rank = SizeScale::new(&style(SizeScaling::Rank), &words);
+		assert_eq!(rank.target_size(4.0), 20);
+		assert!(SizeScale::new(&style(SizeScaling::Log), &words).target_size(4.0) > 11);
+	}
+}
@@ -1,10 +1,10 @@
 use crate::core::error::GlyphWeaveError;
 use crate::layout::common::{
-	Rect, create_progress_bar, descending_font_sizes, finish_progress, is_area_available,
-	occupy_area, pick_color, pick_weighted_word, placement, total_area, update_progress,
+	create_progress_bar, finish_progress, placement, rotation_candidates, total_area,
+	update_progress,
 };
 use crate::layout::{LayoutRequest, LayoutResult, LayoutStrategy};
-use crate::mask::{calculate_text_size, mask_centroid};
+use crate::mask::mask_centroid;
 use rand::RngCore;
 
 const SEARCH_RADIUS_LIMIT: usize = 220;
@@ -27,6 +27,7 @@ impl LayoutStrategy for SpiralGreedyStrategy {
 
 		let mut center = mask_centroid(&mask);
 		let offsets = spiral_offsets(SEARCH_RADIUS_LIMIT);
+		let mut words = request.word_queue();
 		let mut placements = Vec::new();
 		let mut attempts = 0usize;
 		let mut used_area = 0usize;
@@ -39,20 +40,15 @@ impl LayoutStrategy for SpiralGreedyStrategy {
 			}
 
 			attempts += 1;
-			let Some(word_entry) = pick_weighted_word(request.words, rng) else {
+			let Some(word_entry) = words.pick(rng) else {
 				break;
 			};
 
+			let rotations = rotation_candidates(request.style, word_entry, rng);
 			let mut placed = None;
-			'font_search: for size in descending_font_sizes(request.style) {
-				for rotation in &request.style.rotations {
-					let (w, h) = calculate_text_size(
-						&word_entry.text,
-						request.font,
-						size,
-						request.style.padding,
-						*rotation,
-					);
+			'font_search: for size in request.font_sizes(word_entry) {
+				for rotation in &rotations {
+					let footprint = request.footprint(&word_entry.text, size, *rotation);
 					for &(dy, dx) in &offsets {
 						let x = center.0 as isize + dx;
 						let y = center.1 as isize + dy;
@@ -61,31 +57,21 @@ impl LayoutStrategy for SpiralGreedyStrategy {
 							continue;
 						}
 
-						let rect = Rect {
-							x: x as usize,
-							y: y as usize,
-							w,
-							h,
-						};
-
-						if is_area_available(&mask, rect) {
-							placed = Some((size, *rotation, rect));
+						let (x, y) = (x as usize, y as usize);
+						if footprint.fits(&mask, x, y) {
+							placed = Some((size, *rotation, footprint.rect(x, y), footprint));
 							break 'font_search;
 						}
 					}
 				}
 			}
 
-			if let Some((font_size, rotation, rect)) = placed {
-				used_area += occupy_area(&mut mask, rect);
-				let color = pick_color(&request.style.colors, rng);
-				placements.push(placement(
-					&word_entry.text,
-					rect,
-					font_size,
-					color,
-					rotation,
-				));
+			if let Some((font_size, rotation, rect, footprint)) = placed {
+				used_area += footprint.occupy(&mut mask, rect.x, rect.y);
+				let color = request
+					.color_picker
+					.pick(word_entry, &footprint, rect.x, rect.y, rng);
+				placements.push(placement(word_entry, rect, font_size, &color, rotation));
 
 				center = (rect.x, rect.y);
 				if !mask[[
@@ -94,6 +80,9 @@ impl LayoutStrategy for SpiralGreedyStrategy {
 				]] {
 					center = mask_centroid(&mask);
 				}
+				words.placed();
+			} else {
+				words.skip();
 			}
 
 			let ratio_progress = (used_area * 100) / total_usable_area;
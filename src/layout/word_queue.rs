@@ -0,0 +1,118 @@
+use crate::core::model::{PlacementMode, WordEntry};
+use crate::layout::common::pick_weighted_word;
+use rand::RngCore;
+
+const ORDERED_MISS_LIMIT: usize = 24;
+
+#[derive(Debug, Clone)]
+pub struct WordQueue<'a> {
+	words: &'a [WordEntry],
+	ordered: Option<OrderedState>,
+}
+
+#[derive(Debug, Clone)]
+struct OrderedState {
+	order: Vec<usize>,
+	cursor: usize,
+	repeats: usize,
+	placed: usize,
+	misses: usize,
+}
+
+impl OrderedState {
+	fn advance(&mut self) {
+		self.cursor += 1;
+		self.placed = 0;
+		self.misses = 0;
+	}
+}
+
+impl<'a> WordQueue<'a> {
+	pub fn new(words: &'a [WordEntry], mode: PlacementMode) -> Self {
+		let ordered = match mode {
+			PlacementMode::Weighted => None,
+			PlacementMode::Ordered { repeats } => {
+				let mut order: Vec<usize> = (0..words.len()).collect();
+				order.sort_by(|&a, &b| words[b].weight.total_cmp(&words[a].weight));
+				Some(OrderedState {
+					order,
+					cursor: 0,
+					repeats: repeats.max(1),
+					placed: 0,
+					misses: 0,
+				})
+			}
+		};
+
+		Self { words, ordered }
+	}
+
+	pub fn pick(&self, rng: &mut dyn RngCore) -> Option<&'a WordEntry> {
+		match &self.ordered {
+			None => pick_weighted_word(self.words, rng),
+			Some(state) => state.order.get(state.cursor).map(|&idx| &self.words[idx]),
+		}
+	}
+
+	pub fn is_exhausted(&self) -> bool {
+		match &self.ordered {
+			None => self.words.is_empty(),
+			Some(state) => state.cursor >= state.order.len(),
+		}
+	}
+
+	pub fn placed(&mut self) {
+		if let Some(state) = &mut self.ordered {
+			state.placed += 1;
+			state.misses = 0;
+			if state.placed >= state.repeats {
+				state.advance();
+			}
+		}
+	}
+
+	pub fn missed(&mut self) {
+		if let Some(state) = &mut self.ordered {
+			state.misses += 1;
+			if state.misses >= ORDERED_MISS_LIMIT {
+				state.advance();
+			}
+		}
+	}
+
+	pub fn skip(&mut self) {
+		if let Some(state) = &mut self.ordered {
+			state.advance();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::SeedableRng;
+	use rand::rngs::StdRng;
+
+	#[test]
+	fn ordered_queue_walks_words_by_descending_weight() {
+		let words = vec![
+			WordEntry::new("low", 1.0),
+			WordEntry::new("high", 5.0),
+			WordEntry::new("mid", 3.0),
+		];
+		let mut rng = StdRng::seed_from_u64(1);
+		let mut queue = WordQueue::new(&words, PlacementMode::Ordered { repeats: 2 });
+
+		let mut seen = Vec::new();
+		while let Some(word) = queue.pick(&mut rng) {
+			seen.push(word.text.as_str());
+			if word.text == "mid" {
+				queue.skip();
+			} else {
+				queue.placed();
+			}
+		}
+
+		assert_eq!(seen, vec!["high", "high", "mid", "low", "low"]);
+	}
+}
@@ -1,25 +1,34 @@
 pub mod core;
+pub mod export;
 pub mod font;
 pub mod layout;
 pub mod mask;
 pub mod render;
+pub mod subset;
+pub mod text;
+pub mod text_layout;
 
 mod embedded_fonts;
+mod woff;
 
 use crate::core::error::GlyphWeaveError;
-use crate::layout::{LayoutRequest, strategy_for};
-use crate::mask::{build_shape_mask, calculate_auto_font_size, save_mask_image, total_usable_area};
+use crate::layout::{ColorPicker, FootprintCache, LayoutRequest, SizeScale, strategy_for};
+use crate::mask::{load_color_image, resolve_shape_mask, save_mask_image, total_usable_area};
 use rand::SeedableRng;
 use rand::rngs::StdRng;
+use std::collections::HashSet;
 use std::time::Instant;
 
 pub use crate::core::model::{
-	AlgorithmKind, CanvasConfig, CloudPlacement, CloudRequest, CloudResult, CloudStats,
-	FontSizeSpec, RenderOptions, Rotation, ShapeConfig, StyleConfig, WordEntry,
+	AlgorithmKind, CanvasConfig, CloudLayout, CloudPlacement, CloudRequest, CloudRequestSpec,
+	CloudResult, CloudStats, CollisionMode, ColorStrategy, FillRule, FontSizeSpec, FontSource,
+	ImageMaskConfig, MaskChannel, PhraseOptions, PlacementMode, RasterOptions, RenderOptions,
+	Rotation, ShapeConfig, ShapeLayer, ShapeOp, ShapeRegion, ShapeSource, SizeScaling, StyleConfig,
+	SvgMaskSource, SvgTextMode, TextAlign, TextOptions, WordEntry,
 };
 pub use crate::font::{
-	discover_system_font_candidates, load_default_embedded_font, load_font_from_file,
-	load_system_font,
+	FontChain, FontFace, discover_system_font_candidates, load_default_embedded_font,
+	load_font_from_file, load_system_font,
 };
 
 pub fn generate(request: CloudRequest) -> Result<CloudResult, GlyphWeaveError> {
@@ -27,19 +36,9 @@ pub fn generate(request: CloudRequest) -> Result<CloudResult, GlyphWeaveError> {
 
 	let started_at = Instant::now();
 
-	let shape_font_size = match request.shape.font_size {
-		FontSizeSpec::Fixed(size) => size,
-		FontSizeSpec::AutoFit => {
-			calculate_auto_font_size(&request.canvas, &request.shape.text, request.font.as_ref())
-		}
-	};
-
-	let shape_mask = build_shape_mask(
-		&request.canvas,
-		&request.shape.text,
-		request.font.as_ref(),
-		shape_font_size,
-	);
+	let fonts = request.font_chain();
+	let (shape_mask, shape_font_size) =
+		resolve_shape_mask(&request.canvas, &request.shape, &fonts)?;
 
 	if let Some(path) = &request.render.debug_mask_out {
 		save_mask_image(&shape_mask, path)?;
@@ -48,38 +47,61 @@ pub fn generate(request: CloudRequest) -> Result<CloudResult, GlyphWeaveError> {
 	let total_area = total_usable_area(&shape_mask);
 	if total_area == 0 {
 		return Err(GlyphWeaveError::Generation(
-			"shape mask is empty; try a different text/font/canvas combination".to_string(),
+			"shape mask is empty; try a different shape/font/canvas combination".to_string(),
 		));
 	}
 
 	let used_seed = request.seed.unwrap_or_else(rand::random::<u64>);
 	let mut rng = StdRng::seed_from_u64(used_seed);
 
+	let mut color_picker = ColorPicker::new(&request.style, &request.words);
+	if let ColorStrategy::Image { path } = &request.style.color_strategy {
+		let image = load_color_image(&request.canvas, &request.shape, path.as_deref())?;
+		color_picker = color_picker.with_image(image);
+	}
+
 	let layout_req = LayoutRequest {
 		mask: &shape_mask,
 		words: &request.words,
 		style: &request.style,
-		font: request.font.as_ref(),
+		fonts: &fonts,
 		ratio_threshold: request.ratio_threshold,
 		max_try_count: request.max_try_count,
+		placement: request.placement,
 		show_progress: request.render.show_progress,
+		footprints: FootprintCache::default(),
+		size_scale: SizeScale::new(&request.style, &request.words),
+		color_picker,
 	};
 
 	let strategy = strategy_for(request.algorithm);
 	let layout_result = strategy.place(&layout_req, &mut rng)?;
 
-	let svg = render::render_svg(
+	let svg = render::render_svg_with_mode(
 		&request.canvas,
 		&layout_result.placements,
-		&font::font_family_name(request.font.as_ref()),
-	);
+		&fonts,
+		request.render.svg_text,
+	)?;
 
 	let placed_words = layout_result.placements.len();
+	let placed_texts: HashSet<&str> = layout_result
+		.placements
+		.iter()
+		.map(|placement| placement.word.as_str())
+		.collect();
+	let unplaced_words = request
+		.words
+		.iter()
+		.filter(|word| !placed_texts.contains(word.text.as_str()))
+		.map(|word| word.text.clone())
+		.collect();
 	let fill_ratio = layout_result.used_area as f32 / total_area as f32;
 
 	Ok(CloudResult {
 		svg,
 		placements: layout_result.placements,
+		unplaced_words,
 		stats: CloudStats {
 			seed: used_seed,
 			shape_font_size,
@@ -93,25 +115,22 @@ pub fn generate(request: CloudRequest) -> Result<CloudResult, GlyphWeaveError> {
 	})
 }
 
-pub fn rotations_from_degrees(values: &[u16]) -> Result<Vec<Rotation>, GlyphWeaveError> {
+pub fn rotations_from_degrees(values: &[i16]) -> Result<Vec<Rotation>, GlyphWeaveError> {
 	let mut rotations = Vec::new();
 	for value in values {
-		let rotation = match value {
-			0 => Rotation::Deg0,
-			90 => Rotation::Deg90,
-			_ => {
-				return Err(GlyphWeaveError::InvalidConfig(format!(
-					"unsupported rotation '{value}', only 0 and 90 are supported"
-				)));
-			}
-		};
+		if value.unsigned_abs() > 360 {
+			return Err(GlyphWeaveError::InvalidConfig(format!(
+				"unsupported rotation '{value}', expected degrees within -360..=360"
+			)));
+		}
+		let rotation = Rotation::from_degrees(*value as i32);
 		if !rotations.contains(&rotation) {
 			rotations.push(rotation);
 		}
 	}
 
 	if rotations.is_empty() {
-		rotations.push(Rotation::Deg0);
+		rotations.push(Rotation::DEG0);
 	}
 
 	Ok(rotations)
@@ -131,10 +150,7 @@ mod tests {
 				height: 320,
 				margin: 12,
 			},
-			shape: ShapeConfig {
-				text: "AI".to_string(),
-				font_size: FontSizeSpec::AutoFit,
-			},
+			shape: ShapeConfig::new(ShapeSource::Text("AI".to_string()), FontSizeSpec::AutoFit),
 			words: vec![
 				WordEntry::new("Rust", 2.0),
 				WordEntry::new("Cloud", 1.0),
@@ -142,18 +158,25 @@ mod tests {
 			],
 			style: StyleConfig {
 				font_size_range: 12..=24,
+				size_scaling: SizeScaling::Uniform,
 				padding: 0,
 				colors: vec!["#111111".to_string(), "#228833".to_string()],
-				rotations: vec![Rotation::Deg0],
+				color_strategy: ColorStrategy::Random,
+				rotations: vec![Rotation::DEG0],
+				rotation_range: None,
+				collision: CollisionMode::BoundingBox,
 			},
 			algorithm: AlgorithmKind::FastGrid,
+			placement: PlacementMode::Weighted,
 			ratio_threshold: 0.25,
 			max_try_count: 800,
 			seed: Some(1234),
 			font: Arc::new(font),
+			fallback_fonts: Vec::new(),
 			render: RenderOptions {
 				show_progress: false,
 				debug_mask_out: None,
+				svg_text: SvgTextMode::Text,
 			},
 		};
 
@@ -1,31 +1,146 @@
 use crate::core::error::GlyphWeaveError;
-use crate::core::model::{CanvasConfig, Rotation};
-use fontdue::Font;
-use image::{ImageBuffer, Rgba};
+use crate::core::model::{
+	CanvasConfig, FontSizeSpec, FontSource, ImageMaskConfig, MaskChannel, Rotation, ShapeConfig,
+	ShapeLayer, ShapeOp, ShapeSource, SvgMaskSource, TextAlign,
+};
+use crate::font::FontChain;
+use crate::text_layout::LineLayout;
+use image::imageops::{self, FilterType};
+use image::{ImageBuffer, Rgba, RgbaImage};
 use ndarray::Array2;
+use resvg::{tiny_skia, usvg};
 use std::path::Path;
+use std::sync::Arc;
+
+const GLYPH_INK_THRESHOLD: u8 = 64;
 
 pub fn calculate_text_size(
 	text: &str,
-	font: &Font,
+	fonts: &FontChain,
 	font_size: usize,
 	padding: usize,
 	rotation: Rotation,
 ) -> (usize, usize) {
-	let metrics: Vec<_> = text
-		.chars()
-		.map(|c| font.metrics(c, font_size as f32))
-		.collect();
-	let width = metrics.iter().map(|m| m.advance_width).sum::<f32>().ceil() as usize + 2 * padding;
-	let height = metrics.iter().map(|m| m.height).max().unwrap_or(0) + 2 * padding;
+	let line = LineLayout::new(text, fonts, font_size as f32);
+	let width = line.ink_width() + 2 * padding;
+	let height = line.ink_height() + 2 * padding;
+
+	rotated_bounds(width, height, rotation)
+}
+
+pub fn rotated_bounds(width: usize, height: usize, rotation: Rotation) -> (usize, usize) {
+	match rotation.degrees() {
+		0 | 180 => (width, height),
+		90 | -90 => (height, width),
+		_ => {
+			let (sin, cos) = rotation.radians().sin_cos();
+			let (sin, cos) = (sin.abs(), cos.abs());
+			let w = width as f32;
+			let h = height as f32;
+			(
+				(w * cos + h * sin).ceil() as usize,
+				(w * sin + h * cos).ceil() as usize,
+			)
+		}
+	}
+}
+
+pub fn rasterize_text_coverage(
+	text: &str,
+	fonts: &FontChain,
+	font_size: usize,
+	padding: usize,
+	rotation: Rotation,
+) -> Array2<bool> {
+	let line = LineLayout::new(text, fonts, font_size as f32);
+	let ink = line.coverage(fonts);
+	let (height, width) = ink.dim();
+	let mut coverage = Array2::from_elem((height + 2 * padding, width + 2 * padding), false);
+	for ((y, x), value) in ink.indexed_iter() {
+		if *value >= GLYPH_INK_THRESHOLD {
+			coverage[[y + padding, x + padding]] = true;
+		}
+	}
+
+	rotate_mask(&dilate_mask(&coverage, padding), rotation)
+}
+
+pub fn dilate_mask(mask: &Array2<bool>, radius: usize) -> Array2<bool> {
+	if radius == 0 {
+		return mask.clone();
+	}
+
+	let (rows, cols) = mask.dim();
+	let mut horizontal = Array2::from_elem((rows, cols), false);
+	for ((y, x), value) in mask.indexed_iter() {
+		if *value {
+			let from = x.saturating_sub(radius);
+			let to = (x + radius).min(cols - 1);
+			for dx in from..=to {
+				horizontal[[y, dx]] = true;
+			}
+		}
+	}
+
+	let mut dilated = Array2::from_elem((rows, cols), false);
+	for ((y, x), value) in horizontal.indexed_iter() {
+		if *value {
+			let from = y.saturating_sub(radius);
+			let to = (y + radius).min(rows - 1);
+			for dy in from..=to {
+				dilated[[dy, x]] = true;
+			}
+		}
+	}
 
-	match rotation {
-		Rotation::Deg0 => (width, height),
-		Rotation::Deg90 => (height, width),
+	dilated
+}
+
+pub fn rotate_mask(mask: &Array2<bool>, rotation: Rotation) -> Array2<bool> {
+	let (rows, cols) = mask.dim();
+	match rotation.degrees() {
+		0 => return mask.clone(),
+		90 => return Array2::from_shape_fn((cols, rows), |(y, x)| mask[[rows - 1 - x, y]]),
+		-90 => return Array2::from_shape_fn((cols, rows), |(y, x)| mask[[x, cols - 1 - y]]),
+		180 => {
+			return Array2::from_shape_fn((rows, cols), |(y, x)| {
+				mask[[rows - 1 - y, cols - 1 - x]]
+			});
+		}
+		_ => {}
 	}
+
+	let (width, height) = rotated_bounds(cols, rows, rotation);
+	let (sin, cos) = rotation.radians().sin_cos();
+	let (source_cx, source_cy) = (cols as f32 / 2.0, rows as f32 / 2.0);
+	let (target_cx, target_cy) = (width as f32 / 2.0, height as f32 / 2.0);
+
+	Array2::from_shape_fn((height, width), |(y, x)| {
+		let dx = x as f32 + 0.5 - target_cx;
+		let dy = y as f32 + 0.5 - target_cy;
+		let sx = cos * dx + sin * dy + source_cx;
+		let sy = -sin * dx + cos * dy + source_cy;
+
+		// Sample the 2x2 source neighbourhood so thin strokes are never dropped.
+		[(-0.5, -0.5), (0.5, -0.5), (-0.5, 0.5), (0.5, 0.5)]
+			.iter()
+			.any(|(ox, oy)| {
+				let px = (sx + ox).floor();
+				let py = (sy + oy).floor();
+				px >= 0.0
+					&& py >= 0.0 && (px as usize) < cols
+					&& (py as usize) < rows
+					&& mask[[py as usize, px as usize]]
+			})
+	})
 }
 
-pub fn calculate_auto_font_size(canvas: &CanvasConfig, text: &str, font: &Font) -> usize {
+pub fn calculate_auto_font_size(
+	canvas: &CanvasConfig,
+	text: &str,
+	fonts: &FontChain,
+	line_spacing: f32,
+) -> usize {
 	let available_width = canvas.width.saturating_sub(2 * canvas.margin);
 	let available_height = canvas.height.saturating_sub(2 * canvas.margin);
 
@@ -35,7 +150,8 @@ pub fn calculate_auto_font_size(canvas: &CanvasConfig, text: &str, font: &Font)
 
 	while low <= high {
 		let mid = low + (high - low) / 2;
-		let (w, h) = calculate_text_size(text, font, mid, 0, Rotation::Deg0);
+		let lines = shape_lines(text, fonts, mid);
+		let (w, h) = block_size(&lines, line_pitch(fonts, mid, line_spacing));
 		if w <= available_width && h <= available_height {
 			best = mid;
 			low = mid + 1;
@@ -50,20 +166,63 @@ pub fn calculate_auto_font_size(canvas: &CanvasConfig, text: &str, font: &Font)
 	best
 }
 
+fn shape_lines(text: &str, fonts: &FontChain, font_size: usize) -> Vec<LineLayout> {
+	text.split('\n')
+		.map(|line| LineLayout::new(line.trim_end_matches('\r'), fonts, font_size as f32))
+		.collect()
+}
+
+// Line height comes from the primary face so fallback glyphs do not change
+// the spacing of a line.
+fn line_pitch(fonts: &FontChain, font_size: usize, line_spacing: f32) -> usize {
+	let line_height = fonts
+		.primary()
+		.horizontal_line_metrics(font_size as f32)
+		.map(|metrics| metrics.new_line_size)
+		.unwrap_or(font_size as f32);
+	(line_height * line_spacing).round() as usize
+}
+
+// Ink extents of stacked lines whose baselines sit `pitch` apart: the widest
+// line, and the top/bottom rows relative to the first baseline (y down).
+fn block_bounds(lines: &[LineLayout], pitch: usize) -> (usize, i64, i64) {
+	let inked = || {
+		lines
+			.iter()
+			.enumerate()
+			.filter(|(_, line)| line.ink_height() > 0)
+	};
+	let width = lines.iter().map(LineLayout::ink_width).max().unwrap_or(0);
+	let top = inked()
+		.map(|(index, line)| (index * pitch) as i64 - line.top as i64)
+		.min()
+		.unwrap_or(0);
+	let bottom = inked()
+		.map(|(index, line)| (index * pitch) as i64 - line.bottom as i64)
+		.max()
+		.unwrap_or(0);
+	(width, top, bottom)
+}
+
+fn block_size(lines: &[LineLayout], pitch: usize) -> (usize, usize) {
+	let (width, top, bottom) = block_bounds(lines, pitch);
+	(width, (bottom - top) as usize)
+}
+
 pub fn build_shape_mask(
 	canvas: &CanvasConfig,
 	text: &str,
-	font: &Font,
+	fonts: &FontChain,
 	font_size: usize,
+	line_spacing: f32,
+	align: TextAlign,
 ) -> Array2<bool> {
 	let mut mask = Array2::from_elem((canvas.height, canvas.width), false);
 
-	let metrics: Vec<_> = text
-		.chars()
-		.map(|c| font.metrics(c, font_size as f32))
-		.collect();
-	let text_width = metrics.iter().map(|m| m.advance_width).sum::<f32>().ceil() as usize;
-	let text_height = metrics.iter().map(|m| m.height).max().unwrap_or(0);
+	let lines = shape_lines(text, fonts, font_size);
+	let pitch = line_pitch(fonts, font_size, line_spacing);
+	let (text_width, block_top, block_bottom) = block_bounds(&lines, pitch);
+	let text_height = (block_bottom - block_top) as usize;
 
 	let offset_x = canvas.margin
 		+ (canvas
@@ -78,30 +237,270 @@ pub fn build_shape_mask(
 			.saturating_sub(text_height))
 			/ 2;
 
-	let mut cursor_x = offset_x;
+	for (index, line) in lines.iter().enumerate() {
+		let slack = text_width - line.ink_width();
+		let indent = match align {
+			TextAlign::Left => 0,
+			TextAlign::Center => slack / 2,
+			TextAlign::Right => slack,
+		};
+		let top = offset_y as i64 + (index * pitch) as i64 - line.top as i64 - block_top;
+		let coverage = line.coverage(fonts);
+		for ((y, x), value) in coverage.indexed_iter() {
+			if *value <= 127 {
+				continue;
+			}
+			let px = offset_x + indent + x;
+			let py = top + y as i64;
+			if px < canvas.width && py >= 0 && (py as usize) < canvas.height {
+				mask[[py as usize, px]] = true;
+			}
+		}
+	}
 
-	for (ch, glyph_metrics) in text.chars().zip(metrics.iter()) {
-		let (raster_metrics, bitmap) = font.rasterize(ch, font_size as f32);
+	mask
+}
 
-		for y in 0..raster_metrics.height {
-			for x in 0..raster_metrics.width {
-				let pixel = bitmap[y * raster_metrics.width + x];
-				if pixel > 127 {
-					let px = cursor_x + x;
-					let py = offset_y + y;
-					if px < canvas.width && py < canvas.height {
-						mask[[py, px]] = true;
-					}
+pub fn resolve_shape_mask(
+	canvas: &CanvasConfig,
+	shape: &ShapeConfig,
+	fonts: &FontChain,
+) -> Result<(Array2<bool>, usize), GlyphWeaveError> {
+	match &shape.source {
+		ShapeSource::Text(text) => {
+			let shape_fonts = shape
+				.font
+				.as_ref()
+				.map(|font| shape_font_chain(font, fonts))
+				.transpose()?;
+			let fonts = shape_fonts.as_ref().unwrap_or(fonts);
+			let font_size = match shape.font_size {
+				FontSizeSpec::Fixed(size) => size,
+				FontSizeSpec::AutoFit => {
+					calculate_auto_font_size(canvas, text, fonts, shape.line_spacing)
 				}
+			};
+			let mask = build_shape_mask(
+				canvas,
+				text,
+				fonts,
+				font_size,
+				shape.line_spacing,
+				shape.align,
+			);
+			Ok((mask, font_size))
+		}
+		ShapeSource::Image(config) => Ok((build_image_mask(canvas, config)?, 0)),
+		ShapeSource::Svg(source) => Ok((build_svg_mask(canvas, source)?, 0)),
+		ShapeSource::Layers(layers) => {
+			// Layers without a font of their own inherit this shape's font; it
+			// is only loaded by the layers that draw text.
+			let Some(font) = &shape.font else {
+				return Ok((build_layered_mask(canvas, layers, fonts)?, 0));
+			};
+			let layers: Vec<ShapeLayer> = layers
+				.iter()
+				.cloned()
+				.map(|mut layer| {
+					layer.shape.font.get_or_insert_with(|| font.clone());
+					layer
+				})
+				.collect();
+			Ok((build_layered_mask(canvas, &layers, fonts)?, 0))
+		}
+	}
+}
+
+fn shape_font_chain(font: &FontSource, fonts: &FontChain) -> Result<FontChain, GlyphWeaveError> {
+	Ok(FontChain::new(Arc::new(font.load()?)).with_fallbacks(fonts.faces().iter().cloned()))
+}
+
+// Each layer is fitted into its own region, then added to or cut out of the
+// mask in order.
+pub fn build_layered_mask(
+	canvas: &CanvasConfig,
+	layers: &[ShapeLayer],
+	fonts: &FontChain,
+) -> Result<Array2<bool>, GlyphWeaveError> {
+	let mut mask = Array2::from_elem((canvas.height, canvas.width), false);
+
+	for layer in layers {
+		let (region, left, top) = match layer.region {
+			Some(region) => (
+				CanvasConfig {
+					width: region.width,
+					height: region.height,
+					margin: 0,
+				},
+				region.x,
+				region.y,
+			),
+			None => (canvas.clone(), 0, 0),
+		};
+		let (layer_mask, _) = resolve_shape_mask(&region, &layer.shape, fonts)?;
+
+		let inside = layer.op == ShapeOp::Union;
+		for ((y, x), covered) in layer_mask.indexed_iter() {
+			if !covered {
+				continue;
+			}
+			if let Some(cell) = mask.get_mut([top + y, left + x]) {
+				*cell = inside;
 			}
 		}
+	}
+
+	Ok(mask)
+}
 
-		cursor_x += glyph_metrics.advance_width.ceil() as usize;
+pub fn build_svg_mask(
+	canvas: &CanvasConfig,
+	source: &SvgMaskSource,
+) -> Result<Array2<bool>, GlyphWeaveError> {
+	let document = match source {
+		SvgMaskSource::File(path) => std::fs::read_to_string(path)?,
+		SvgMaskSource::Document(document) => document.clone(),
+		SvgMaskSource::Path { data, fill_rule } => format!(
+			r#"<svg xmlns="http://www.w3.org/2000/svg"><path d="{}" fill-rule="{}"/></svg>"#,
+			escape_xml_attribute(data),
+			fill_rule.svg_name()
+		),
+	};
+
+	let tree = usvg::Tree::from_str(&document, &usvg::Options::default()).map_err(|err| {
+		GlyphWeaveError::InvalidConfig(format!("failed to parse SVG shape: {err}"))
+	})?;
+
+	let mut mask = Array2::from_elem((canvas.height, canvas.width), false);
+	let bounds = tree.root().abs_bounding_box();
+	let available_width = canvas.width.saturating_sub(2 * canvas.margin) as f32;
+	let available_height = canvas.height.saturating_sub(2 * canvas.margin) as f32;
+	if bounds.width() <= 0.0 || bounds.height() <= 0.0 {
+		return Ok(mask);
+	}
+
+	let scale = (available_width / bounds.width()).min(available_height / bounds.height());
+	let offset_x = canvas.margin as f32 + (available_width - bounds.width() * scale) / 2.0
+		- bounds.x() * scale;
+	let offset_y = canvas.margin as f32 + (available_height - bounds.height() * scale) / 2.0
+		- bounds.y() * scale;
+
+	let Some(mut pixmap) = tiny_skia::Pixmap::new(canvas.width as u32, canvas.height as u32) else {
+		return Ok(mask);
+	};
+	let transform = tiny_skia::Transform::from_row(scale, 0.0, 0.0, scale, offset_x, offset_y);
+	resvg::render(&tree, transform, &mut pixmap.as_mut());
+
+	for (index, pixel) in pixmap.pixels().iter().enumerate() {
+		if pixel.alpha() > 127 {
+			mask[[index / canvas.width, index % canvas.width]] = true;
+		}
+	}
+
+	Ok(mask)
+}
+
+fn escape_xml_attribute(value: &str) -> String {
+	value
+		.replace('&', "&amp;")
+		.replace('"', "&quot;")
+		.replace('<', "&lt;")
+}
+
+pub fn build_image_mask(
+	canvas: &CanvasConfig,
+	config: &ImageMaskConfig,
+) -> Result<Array2<bool>, GlyphWeaveError> {
+	let image = image::open(&config.path)?.to_rgba8();
+	Ok(image_to_mask(canvas, &image, config))
+}
+
+pub fn image_to_mask(
+	canvas: &CanvasConfig,
+	image: &RgbaImage,
+	config: &ImageMaskConfig,
+) -> Array2<bool> {
+	let mut mask = Array2::from_elem((canvas.height, canvas.width), false);
+
+	let available_width = canvas.width.saturating_sub(2 * canvas.margin);
+	let available_height = canvas.height.saturating_sub(2 * canvas.margin);
+	if image.width() == 0 || image.height() == 0 || available_width == 0 || available_height == 0 {
+		return mask;
+	}
+
+	let (scaled, offset_x, offset_y) = fit_image(canvas, image);
+
+	let channel = match config.channel {
+		MaskChannel::Auto if image.pixels().any(|pixel| pixel[3] < u8::MAX) => MaskChannel::Alpha,
+		MaskChannel::Auto => MaskChannel::Luminance,
+		channel => channel,
+	};
+
+	for (x, y, pixel) in scaled.enumerate_pixels() {
+		let inside = match channel {
+			MaskChannel::Alpha => pixel[3] >= config.threshold,
+			_ => pixel_luminance_over_white(pixel) < config.threshold,
+		};
+		if inside != config.invert {
+			mask[[offset_y + y as usize, offset_x + x as usize]] = true;
+		}
 	}
 
 	mask
 }
 
+// Scales the image to fit inside the canvas margins and returns it with the
+// offset that centers it. Callers check that the image and area are non-empty.
+fn fit_image(canvas: &CanvasConfig, image: &RgbaImage) -> (RgbaImage, usize, usize) {
+	let available_width = canvas.width.saturating_sub(2 * canvas.margin);
+	let available_height = canvas.height.saturating_sub(2 * canvas.margin);
+	let scale = (available_width as f32 / image.width() as f32)
+		.min(available_height as f32 / image.height() as f32);
+	let scaled_width = ((image.width() as f32 * scale).round() as u32).max(1);
+	let scaled_height = ((image.height() as f32 * scale).round() as u32).max(1);
+	let scaled = imageops::resize(image, scaled_width, scaled_height, FilterType::Triangle);
+
+	let offset_x = canvas.margin + available_width.saturating_sub(scaled_width as usize) / 2;
+	let offset_y = canvas.margin + available_height.saturating_sub(scaled_height as usize) / 2;
+	(scaled, offset_x, offset_y)
+}
+
+/// Loads the reference picture for `ColorStrategy::Image`, placed on a
+/// canvas-sized transparent image exactly where an image mask would put it.
+pub fn load_color_image(
+	canvas: &CanvasConfig,
+	shape: &ShapeConfig,
+	path: Option<&Path>,
+) -> Result<RgbaImage, GlyphWeaveError> {
+	let path = match (path, &shape.source) {
+		(Some(path), _) => path,
+		(None, ShapeSource::Image(config)) => config.path.as_path(),
+		(None, _) => {
+			return Err(GlyphWeaveError::InvalidConfig(
+				"image colors need a color image path or an image shape".to_string(),
+			));
+		}
+	};
+
+	let image = image::open(path)?.to_rgba8();
+	let mut placed = RgbaImage::new(canvas.width as u32, canvas.height as u32);
+	let available_width = canvas.width.saturating_sub(2 * canvas.margin);
+	let available_height = canvas.height.saturating_sub(2 * canvas.margin);
+	if image.width() == 0 || image.height() == 0 || available_width == 0 || available_height == 0 {
+		return Ok(placed);
+	}
+
+	let (scaled, offset_x, offset_y) = fit_image(canvas, &image);
+	imageops::replace(&mut placed, &scaled, offset_x as i64, offset_y as i64);
+	Ok(placed)
+}
+
+fn pixel_luminance_over_white(pixel: &Rgba<u8>) -> u8 {
+	let alpha = pixel[3] as f32 / 255.0;
+	let luminance = 0.2126 * pixel[0] as f32 + 0.7152 * pixel[1] as f32 + 0.0722 * pixel[2] as f32;
+	(luminance * alpha + 255.0 * (1.0 - alpha)).round() as u8
+}
+
 pub fn total_usable_area(mask: &Array2<bool>) -> usize {
 	mask.iter().filter(|&&value| value).count()
 }
@@ -148,23 +547,210 @@ pub fn save_mask_image(mask: &Array2<bool>, path: &Path) -> Result<(), GlyphWeav
 	Ok(())
 }
 
-#[cfg(all(test, feature = "embedded_fonts"))]
+#[cfg(test)]
 mod tests {
 	use super::*;
+	use crate::core::model::FillRule;
 
+	#[test]
+	fn dilation_and_rotation_keep_expected_shape() {
+		let mut mask = Array2::from_elem((3, 5), false);
+		mask[[1, 2]] = true;
+
+		let dilated = dilate_mask(&mask, 1);
+		assert_eq!(total_usable_area(&dilated), 9);
+		assert!(!dilated[[1, 0]]);
+
+		mask[[0, 4]] = true;
+		let rotated = rotate_mask(&mask, Rotation::DEG90);
+		assert_eq!(rotated.dim(), (5, 3));
+		assert!(rotated[[2, 1]]);
+		assert!(rotated[[4, 2]]);
+
+		let back = rotate_mask(&rotated, Rotation::from_degrees(-90));
+		assert_eq!(back, mask);
+
+		let diagonal = rotate_mask(
+			&Array2::from_elem((4, 20), true),
+			Rotation::from_degrees(45),
+		);
+		assert_eq!(
+			diagonal.dim(),
+			rotated_bounds(20, 4, Rotation::from_degrees(45))
+		);
+		assert!(diagonal[[8, 8]]);
+		assert!(!diagonal[[0, diagonal.ncols() - 1]]);
+		assert!(!diagonal[[diagonal.nrows() - 1, 0]]);
+	}
+
+	#[test]
+	fn image_mask_is_scaled_and_centered() {
+		let mut image = RgbaImage::from_pixel(20, 10, Rgba([255, 255, 255, 255]));
+		for y in 0..10 {
+			for x in 0..10 {
+				image.put_pixel(x, y, Rgba([0, 0, 0, 255]));
+			}
+		}
+		let canvas = CanvasConfig {
+			width: 100,
+			height: 100,
+			margin: 10,
+		};
+
+		let mask = image_to_mask(&canvas, &image, &ImageMaskConfig::new("logo.png"));
+		assert!(mask[[50, 20]]);
+		assert!(!mask[[50, 70]]);
+		assert!(!mask[[20, 20]]);
+
+		let inverted = ImageMaskConfig {
+			invert: true,
+			..ImageMaskConfig::new("logo.png")
+		};
+		let mask = image_to_mask(&canvas, &image, &inverted);
+		assert!(!mask[[50, 20]]);
+		assert!(mask[[50, 70]]);
+		assert!(!mask[[5, 5]]);
+	}
+
+	#[test]
+	fn svg_path_mask_honours_fill_rule() {
+		let canvas = CanvasConfig {
+			width: 100,
+			height: 100,
+			margin: 0,
+		};
+		let data = "M0 0 H10 V10 H0 Z M3 3 H7 V7 H3 Z";
+
+		let nonzero = build_svg_mask(
+			&canvas,
+			&SvgMaskSource::Path {
+				data: data.to_string(),
+				fill_rule: FillRule::NonZero,
+			},
+		)
+		.expect("path should rasterize");
+		assert!(nonzero[[50, 50]]);
+		assert!(nonzero[[10, 10]]);
+
+		let evenodd = build_svg_mask(
+			&canvas,
+			&SvgMaskSource::Path {
+				data: data.to_string(),
+				fill_rule: FillRule::EvenOdd,
+			},
+		)
+		.expect("path should rasterize");
+		assert!(!evenodd[[50, 50]]);
+		assert!(evenodd[[10, 10]]);
+	}
+
+	#[cfg(feature = "embedded_fonts")]
 	#[test]
 	fn auto_font_size_and_mask_are_valid() {
-		let font = crate::font::load_default_embedded_font().expect("embedded font should load");
+		let font = FontChain::from(
+			crate::font::load_default_embedded_font().expect("embedded font should load"),
+		);
 		let canvas = CanvasConfig {
 			width: 800,
 			height: 400,
 			margin: 20,
 		};
-		let size = calculate_auto_font_size(&canvas, "HELLO", &font);
+		let size = calculate_auto_font_size(&canvas, "HELLO", &font, 1.0);
 		assert!(size > 0);
 
-		let mask = build_shape_mask(&canvas, "HELLO", &font, size);
+		let mask = build_shape_mask(&canvas, "HELLO", &font, size, 1.0, TextAlign::Center);
 		assert_eq!(mask.dim(), (400, 800));
 		assert!(total_usable_area(&mask) > 0);
 	}
+
+	#[cfg(feature = "embedded_fonts")]
+	#[test]
+	fn multi_line_text_stacks_aligned_lines() {
+		let font = FontChain::from(
+			crate::font::load_default_embedded_font().expect("embedded font should load"),
+		);
+		let canvas = CanvasConfig {
+			width: 600,
+			height: 400,
+			margin: 10,
+		};
+		let text = "I\nWWWW";
+		let size = calculate_auto_font_size(&canvas, text, &font, 1.2);
+		assert!(size > calculate_auto_font_size(&canvas, "IWWWW", &font, 1.2));
+
+		let ink_columns = |mask: &Array2<bool>, rows: std::ops::Range<usize>| {
+			let columns: Vec<usize> = rows
+				.flat_map(|y| (0..canvas.width).filter(move |&x| mask[[y, x]]))
+				.collect();
+			(columns.iter().min().copied(), columns.iter().max().copied())
+		};
+		let bands = |mask: &Array2<bool>| {
+			let rows: Vec<usize> = (0..canvas.height)
+				.filter(|&y| mask.row(y).iter().any(|cell| *cell))
+				.collect();
+			let gap = rows
+				.windows(2)
+				.position(|pair| pair[1] - pair[0] > 1)
+				.expect("lines should be separated by blank rows");
+			(
+				rows[0]..rows[gap] + 1,
+				rows[gap + 1]..rows[rows.len() - 1] + 1,
+			)
+		};
+
+		let left = build_shape_mask(&canvas, text, &font, size, 1.2, TextAlign::Left);
+		let (top, bottom) = bands(&left);
+		let (top_min, _) = ink_columns(&left, top);
+		let (bottom_min, _) = ink_columns(&left, bottom);
+		assert!(top_min.unwrap().abs_diff(bottom_min.unwrap()) < size / 4);
+
+		let right = build_shape_mask(&canvas, text, &font, size, 1.2, TextAlign::Right);
+		let (top, bottom) = bands(&right);
+		let (_, top_max) = ink_columns(&right, top);
+		let (_, bottom_max) = ink_columns(&right, bottom);
+		assert!(top_max.unwrap().abs_diff(bottom_max.unwrap()) < size / 4);
+	}
+
+	#[cfg(feature = "embedded_fonts")]
+	#[test]
+	fn layers_union_and_subtract_in_their_regions() {
+		let font = FontChain::from(
+			crate::font::load_default_embedded_font().expect("embedded font should load"),
+		);
+		let canvas = CanvasConfig {
+			width: 200,
+			height: 100,
+			margin: 0,
+		};
+		use crate::core::model::ShapeRegion;
+
+		let square = |x, y, size, op| ShapeLayer {
+			shape: ShapeConfig::new(
+				ShapeSource::Svg(SvgMaskSource::Path {
+					data: "M0 0 H10 V10 H0 Z".to_string(),
+					fill_rule: FillRule::NonZero,
+				}),
+				FontSizeSpec::AutoFit,
+			),
+			region: Some(ShapeRegion {
+				x,
+				y,
+				width: size,
+				height: size,
+			}),
+			op,
+		};
+		let layers = vec![
+			square(0, 0, 60, ShapeOp::Union),
+			square(120, 20, 60, ShapeOp::Union),
+			square(0, 0, 20, ShapeOp::Subtract),
+		];
+
+		let mask = build_layered_mask(&canvas, &layers, &font).expect("layers should combine");
+		assert!(mask[[40, 40]]);
+		assert!(!mask[[10, 10]]);
+		assert!(mask[[50, 150]]);
+		assert!(!mask[[50, 90]]);
+		assert!(!mask[[10, 150]]);
+	}
 }
@@ -1,38 +1,384 @@
-use crate::core::model::{CanvasConfig, CloudPlacement, Rotation};
+use crate::core::error::GlyphWeaveError;
+use crate::core::model::{
+	CanvasConfig, CloudLayout, CloudPlacement, RasterOptions, Rotation, SvgTextMode,
+};
+use crate::font::{FontChain, css_family_name, font_family_name};
+use crate::mask::rotated_bounds;
+use crate::subset::{is_cff_font, subset_font};
+use crate::text_layout::LineLayout;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use image::{DynamicImage, ImageFormat, Rgba, RgbaImage};
+use log::warn;
+use ndarray::Array2;
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
 use svg::Document;
-use svg::node::element::Text;
+use svg::Node;
+use svg::node::element::{Anchor, Path as SvgPath, Style, Text};
+use ttf_parser::{GlyphId, OutlineBuilder};
 
 pub fn render_svg(
 	canvas: &CanvasConfig,
 	placements: &[CloudPlacement],
-	font_family: &str,
+	fonts: &FontChain,
 ) -> String {
-	let mut doc = Document::new()
+	add_text_elements(svg_document(canvas), placements, fonts).to_string()
+}
+
+pub fn render_svg_with_mode(
+	canvas: &CanvasConfig,
+	placements: &[CloudPlacement],
+	fonts: &FontChain,
+	mode: SvgTextMode,
+) -> Result<String, GlyphWeaveError> {
+	match mode {
+		SvgTextMode::Text => Ok(render_svg(canvas, placements, fonts)),
+		SvgTextMode::Outlines => render_svg_outlines(canvas, placements, fonts),
+		SvgTextMode::EmbeddedFont => render_svg_embedded(canvas, placements, fonts),
+	}
+}
+
+// Re-renders stored placements; the layout strategies are not run again.
+pub fn render_layout_svg(
+	layout: &CloudLayout,
+	fonts: &FontChain,
+	mode: SvgTextMode,
+) -> Result<String, GlyphWeaveError> {
+	render_svg_with_mode(&layout.canvas, &layout.placements, fonts, mode)
+}
+
+pub fn render_layout_raster(
+	layout: &CloudLayout,
+	fonts: &FontChain,
+	options: &RasterOptions,
+) -> Result<RgbaImage, GlyphWeaveError> {
+	render_raster(&layout.canvas, &layout.placements, fonts, options)
+}
+
+// Every face of the chain that draws at least one character is subset to
+// those characters and embedded under its own family name.
+pub fn render_svg_embedded(
+	canvas: &CanvasConfig,
+	placements: &[CloudPlacement],
+	fonts: &FontChain,
+) -> Result<String, GlyphWeaveError> {
+	let mut texts = vec![String::new(); fonts.faces().len()];
+	for ch in placements.iter().flat_map(|p| p.word.chars()) {
+		texts[fonts.resolve(ch)].push(ch);
+	}
+
+	let mut css = String::new();
+	for (index, (face, text)) in fonts.faces().iter().zip(&texts).enumerate() {
+		if index > 0 && text.is_empty() {
+			continue;
+		}
+		let subset = subset_font(face, text)?;
+		let family = css_family_name(&font_family_name(face));
+		let (mime, format) = if is_cff_font(face) {
+			warn!(
+				"embedding {family} whole ({} KiB): CFF outlines are not subset",
+				subset.len() / 1024
+			);
+			("font/otf", "opentype")
+		} else {
+			("font/ttf", "truetype")
+		};
+		let _ = write!(
+			css,
+			"@font-face{{font-family:{family};src:url(data:{mime};base64,{}) format(\"{format}\");}}",
+			BASE64.encode(subset)
+		);
+	}
+
+	let doc = svg_document(canvas).add(Style::new(css));
+	Ok(add_text_elements(doc, placements, fonts).to_string())
+}
+
+// Each word names the faces that draw its characters.
+fn add_text_elements(
+	mut doc: Document,
+	placements: &[CloudPlacement],
+	fonts: &FontChain,
+) -> Document {
+	for placement in placements {
+		let (cx, cy) = box_center(placement);
+		let (x, y) = baseline_origin(placement, fonts);
+		let mut text = Text::new(&placement.word)
+			.set("x", x)
+			.set("y", y)
+			.set("font-family", fonts.family_list_for(&placement.word))
+			.set("font-size", placement.font_size)
+			.set("fill", placement.color.as_str());
+		if placement.rotation != Rotation::DEG0 {
+			text = text.set(
+				"transform",
+				format!("rotate({} {cx} {cy})", placement.rotation.degrees()),
+			);
+		}
+		if let Some(class) = &placement.class {
+			text = text.set("class", class.as_str());
+		}
+		doc = add_word_node(doc, text, placement);
+	}
+
+	doc
+}
+
+fn box_center(placement: &CloudPlacement) -> (f32, f32) {
+	(
+		placement.x as f32 + placement.width as f32 / 2.0,
+		placement.y as f32 + placement.height as f32 / 2.0,
+	)
+}
+
+// Where the line starts on its baseline so that the word's ink is centred in
+// its reserved box, matching the footprint the layout measured. Rotated words
+// are turned around the same centre.
+fn baseline_origin(placement: &CloudPlacement, fonts: &FontChain) -> (f32, f32) {
+	let line = LineLayout::new(&placement.word, fonts, placement.font_size as f32);
+	let (cx, cy) = box_center(placement);
+	let (dx, dy) = line.origin_from_center();
+	(round_coordinate(cx + dx), round_coordinate(cy + dy))
+}
+
+fn round_coordinate(value: f32) -> f32 {
+	(value * 100.0).round() / 100.0
+}
+
+// Words with a link are wrapped in an `<a>` so the whole word is clickable.
+fn add_word_node(
+	doc: Document,
+	node: impl Into<Box<dyn Node>>,
+	placement: &CloudPlacement,
+) -> Document {
+	match &placement.link {
+		Some(link) => doc.add(Anchor::new().set("href", link.as_str()).add(node)),
+		None => doc.add(node),
+	}
+}
+
+pub fn render_svg_outlines(
+	canvas: &CanvasConfig,
+	placements: &[CloudPlacement],
+	fonts: &FontChain,
+) -> Result<String, GlyphWeaveError> {
+	let faces = fonts
+		.faces()
+		.iter()
+		.map(|face| face.parse_outlines())
+		.collect::<Result<Vec<_>, _>>()?;
+	let mut doc = svg_document(canvas);
+
+	for placement in placements {
+		let line = LineLayout::new(&placement.word, fonts, placement.font_size as f32);
+		let (cx, cy) = box_center(placement);
+		let (dx, dy) = line.origin_from_center();
+		let mut outline = OutlinePath {
+			data: String::new(),
+			origin_x: cx + dx,
+			baseline: cy + dy,
+			scale: 1.0,
+		};
+		for glyph in &line.glyphs {
+			let face = &faces[glyph.font];
+			outline.origin_x = cx + dx + glyph.x;
+			outline.scale = placement.font_size as f32 / face.units_per_em() as f32;
+			let id = face.glyph_index(glyph.ch).unwrap_or(GlyphId(0));
+			face.outline_glyph(id, &mut outline);
+		}
+
+		let mut path = SvgPath::new()
+			.set("d", outline.data.trim_end())
+			.set("fill", placement.color.as_str());
+		if placement.rotation != Rotation::DEG0 {
+			path = path.set(
+				"transform",
+				format!("rotate({} {cx} {cy})", placement.rotation.degrees()),
+			);
+		}
+		if let Some(class) = &placement.class {
+			path = path.set("class", class.as_str());
+		}
+		doc = add_word_node(doc, path, placement);
+	}
+
+	Ok(doc.to_string())
+}
+
+fn svg_document(canvas: &CanvasConfig) -> Document {
+	Document::new()
 		.set("width", canvas.width)
 		.set("height", canvas.height)
 		.set("viewBox", (0, 0, canvas.width, canvas.height))
 		.set("xmlns", "http://www.w3.org/2000/svg")
-		.set("xmlns:xlink", "http://www.w3.org/1999/xlink");
+		.set("xmlns:xlink", "http://www.w3.org/1999/xlink")
+}
+
+struct OutlinePath {
+	data: String,
+	origin_x: f32,
+	baseline: f32,
+	scale: f32,
+}
+
+impl OutlinePath {
+	fn point(&self, x: f32, y: f32) -> (f32, f32) {
+		(
+			round_coordinate(self.origin_x + x * self.scale),
+			round_coordinate(self.baseline - y * self.scale),
+		)
+	}
+}
+
+impl OutlineBuilder for OutlinePath {
+	fn move_to(&mut self, x: f32, y: f32) {
+		let (x, y) = self.point(x, y);
+		let _ = write!(self.data, "M{x} {y} ");
+	}
+
+	fn line_to(&mut self, x: f32, y: f32) {
+		let (x, y) = self.point(x, y);
+		let _ = write!(self.data, "L{x} {y} ");
+	}
+
+	fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+		let (x1, y1) = self.point(x1, y1);
+		let (x, y) = self.point(x, y);
+		let _ = write!(self.data, "Q{x1} {y1} {x} {y} ");
+	}
+
+	fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+		let (x1, y1) = self.point(x1, y1);
+		let (x2, y2) = self.point(x2, y2);
+		let (x, y) = self.point(x, y);
+		let _ = write!(self.data, "C{x1} {y1} {x2} {y2} {x} {y} ");
+	}
+
+	fn close(&mut self) {
+		self.data.push_str("Z ");
+	}
+}
+
+pub fn render_raster(
+	canvas: &CanvasConfig,
+	placements: &[CloudPlacement],
+	fonts: &FontChain,
+	options: &RasterOptions,
+) -> Result<RgbaImage, GlyphWeaveError> {
+	let scale = options.scale;
+	if !scale.is_finite() || scale <= 0.0 {
+		return Err(GlyphWeaveError::InvalidConfig(
+			"raster scale must be a positive number".to_string(),
+		));
+	}
+
+	let width = ((canvas.width as f32 * scale).round() as u32).max(1);
+	let height = ((canvas.height as f32 * scale).round() as u32).max(1);
+	let background = match &options.background {
+		Some(color) => parse_color(color)?,
+		None => [0, 0, 0, 0],
+	};
+	let mut image = RgbaImage::from_pixel(width, height, Rgba(background));
 
 	for placement in placements {
-		let mut element = Text::new(&placement.word)
-			.set("x", placement.x)
-			.set("y", placement.y)
-			.set("font-family", font_family)
-			.set("font-size", placement.font_size)
-			.set("fill", placement.color.as_str())
-			.set("dominant-baseline", "hanging")
-			.set("text-anchor", "start");
+		let color = parse_color(&placement.color)?;
+		let coverage = rotate_coverage(
+			&text_coverage(&placement.word, fonts, placement.font_size as f32 * scale),
+			placement.rotation,
+		);
 
-		if placement.rotation == Rotation::Deg90 {
-			element = element.set(
-				"transform",
-				format!("rotate(90 {} {})", placement.x, placement.y),
-			);
+		// Centre the ink inside the reserved box, mirroring the SVG anchor for rotated words.
+		let (rows, cols) = coverage.dim();
+		let cx = (placement.x as f32 + placement.width as f32 / 2.0) * scale;
+		let cy = (placement.y as f32 + placement.height as f32 / 2.0) * scale;
+		let left = (cx - cols as f32 / 2.0).round() as i64;
+		let top = (cy - rows as f32 / 2.0).round() as i64;
+
+		for ((y, x), alpha) in coverage.indexed_iter() {
+			let (px, py) = (left + x as i64, top + y as i64);
+			if *alpha <= 0.0 || px < 0 || py < 0 || px >= width as i64 || py >= height as i64 {
+				continue;
+			}
+			blend_pixel(image.get_pixel_mut(px as u32, py as u32), color, *alpha);
 		}
+	}
 
-		doc = doc.add(element);
+	Ok(image)
+}
+
+pub fn save_raster_image(image: &RgbaImage, path: &Path) -> Result<(), GlyphWeaveError> {
+	if ImageFormat::from_path(path)? == ImageFormat::Jpeg {
+		// JPEG has no alpha channel, so flatten transparent areas onto white.
+		let mut flattened = RgbaImage::from_pixel(image.width(), image.height(), Rgba([255; 4]));
+		for (target, source) in flattened.pixels_mut().zip(image.pixels()) {
+			blend_pixel(target, source.0, 1.0);
+		}
+		DynamicImage::ImageRgba8(flattened).to_rgb8().save(path)?;
+		return Ok(());
 	}
 
-	doc.to_string()
+	image.save(path)?;
+	Ok(())
+}
+
+pub fn parse_color(text: &str) -> Result<[u8; 4], GlyphWeaveError> {
+	let color = svgtypes::Color::from_str(text.trim())
+		.map_err(|_| GlyphWeaveError::InvalidConfig(format!("invalid color '{text}'")))?;
+	Ok([color.red, color.green, color.blue, color.alpha])
+}
+
+fn text_coverage(text: &str, fonts: &FontChain, font_size: f32) -> Array2<f32> {
+	LineLayout::new(text, fonts, font_size)
+		.coverage(fonts)
+		.mapv(|value| value as f32 / 255.0)
+}
+
+fn rotate_coverage(coverage: &Array2<f32>, rotation: Rotation) -> Array2<f32> {
+	if rotation == Rotation::DEG0 {
+		return coverage.clone();
+	}
+
+	let (rows, cols) = coverage.dim();
+	let (width, height) = rotated_bounds(cols, rows, rotation);
+	let (sin, cos) = rotation.radians().sin_cos();
+	let (source_cx, source_cy) = (cols as f32 / 2.0, rows as f32 / 2.0);
+	let (target_cx, target_cy) = (width as f32 / 2.0, height as f32 / 2.0);
+	let sample = |x: f32, y: f32| -> f32 {
+		if x < 0.0 || y < 0.0 || x >= cols as f32 || y >= rows as f32 {
+			return 0.0;
+		}
+		coverage[[y as usize, x as usize]]
+	};
+
+	Array2::from_shape_fn((height, width), |(y, x)| {
+		let dx = x as f32 + 0.5 - target_cx;
+		let dy = y as f32 + 0.5 - target_cy;
+		let sx = cos * dx + sin * dy + source_cx - 0.5;
+		let sy = -sin * dx + cos * dy + source_cy - 0.5;
+
+		let (x0, y0) = (sx.floor(), sy.floor());
+		let (fx, fy) = (sx - x0, sy - y0);
+		let top = sample(x0, y0) * (1.0 - fx) + sample(x0 + 1.0, y0) * fx;
+		let bottom = sample(x0, y0 + 1.0) * (1.0 - fx) + sample(x0 + 1.0, y0 + 1.0) * fx;
+		top * (1.0 - fy) + bottom * fy
+	})
+}
+
+fn blend_pixel(target: &mut Rgba<u8>, color: [u8; 4], coverage: f32) {
+	let source_alpha = coverage.clamp(0.0, 1.0) * color[3] as f32 / 255.0;
+	let target_alpha = target[3] as f32 / 255.0;
+	let out_alpha = source_alpha + target_alpha * (1.0 - source_alpha);
+	if out_alpha <= 0.0 {
+		return;
+	}
+
+	for channel in 0..3 {
+		let value = (color[channel] as f32 * source_alpha
+			+ target[channel] as f32 * target_alpha * (1.0 - source_alpha))
+			/ out_alpha;
+		target[channel] = value.round() as u8;
+	}
+	target[3] = (out_alpha * 255.0).round() as u8;
 }
@@ -0,0 +1,353 @@
+use crate::core::error::GlyphWeaveError;
+use crate::font::FontFace;
+use std::collections::BTreeSet;
+use ttf_parser::Tag;
+
+// Tables copied into a subset. Everything else either indexes glyphs by id
+// (GSUB, GPOS, kern, vmtx, colour and bitmap tables) or is rebuilt below.
+const KEPT_TABLES: [&[u8; 4]; 8] = [
+	b"head", b"hhea", b"OS/2", b"name", b"cvt ", b"fpgm", b"prep", b"gasp",
+];
+
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+
+const TRUETYPE_VERSION: u32 = 0x0001_0000;
+const CFF_VERSION: u32 = u32::from_be_bytes(*b"OTTO");
+
+pub fn is_cff_font(font: &FontFace) -> bool {
+	font.parse_outlines().is_ok_and(|face| {
+		let raw = face.raw_face();
+		raw.table(Tag::from_bytes(b"CFF ")).is_some()
+			|| raw.table(Tag::from_bytes(b"CFF2")).is_some()
+	})
+}
+
+// Keeps the glyphs needed for `text` (plus `.notdef` and composite components),
+// renumbers them in their original order and rebuilds glyf, loca, hmtx, cmap,
+// maxp and post to match. Kerning and substitutions are not carried over.
+// CFF-flavoured fonts are returned whole, pulled out of their collection if
+// they came from one.
+pub fn subset_font(font: &FontFace, text: &str) -> Result<Vec<u8>, GlyphWeaveError> {
+	let face = font.parse_outlines()?;
+	let raw = face.raw_face();
+	let table = |tag: &[u8; 4]| raw.table(Tag::from_bytes(tag));
+	let (Some(glyf), Some(loca), Some(head), Some(hhea), Some(maxp)) = (
+		table(b"glyf"),
+		table(b"loca"),
+		table(b"head"),
+		table(b"hhea"),
+		table(b"maxp"),
+	) else {
+		if !font.is_collection() {
+			return Ok(font.data().to_vec());
+		}
+		let tables = raw
+			.table_records
+			.into_iter()
+			.map(|record| {
+				raw.table(record.tag)
+					.map(|data| (record.tag.to_bytes(), data.to_vec()))
+					.ok_or_else(|| invalid_font("table record points outside the file"))
+			})
+			.collect::<Result<_, _>>()?;
+		return Ok(write_sfnt(CFF_VERSION, tables));
+	};
+	if head.len() < 54 || hhea.len() < 36 || maxp.len() < 6 {
+		return Err(invalid_font("head, hhea or maxp table is truncated"));
+	}
+
+	let num_glyphs = face.number_of_glyphs();
+	let long_loca = read_u16(head, 50) == Some(1);
+	let offsets = (0..=num_glyphs as usize)
+		.map(|index| {
+			if long_loca {
+				read_u32(loca, index * 4).map(|offset| offset as usize)
+			} else {
+				read_u16(loca, index * 2).map(|offset| offset as usize * 2)
+			}
+		})
+		.collect::<Option<Vec<_>>>()
+		.ok_or_else(|| invalid_font("loca table is truncated"))?;
+	let glyph_data = |glyph: u16| -> &[u8] {
+		let start = offsets[glyph as usize].min(glyf.len());
+		let end = offsets[glyph as usize + 1].clamp(start, glyf.len());
+		&glyf[start..end]
+	};
+
+	let mut chars: Vec<(char, u16)> = text
+		.chars()
+		.filter_map(|c| face.glyph_index(c).map(|id| (c, id.0)))
+		.collect();
+	chars.sort_unstable();
+	chars.dedup();
+
+	let mut keep = BTreeSet::new();
+	let mut pending = vec![0u16];
+	pending.extend(chars.iter().map(|(_, glyph)| *glyph));
+	while let Some(glyph) = pending.pop() {
+		if glyph < num_glyphs && keep.insert(glyph) {
+			pending.extend(
+				composite_components(glyph_data(glyph))
+					.into_iter()
+					.map(|(_, component)| component),
+			);
+		}
+	}
+	let kept: Vec<u16> = keep.into_iter().collect();
+	let new_id = |glyph: u16| kept.binary_search(&glyph).unwrap_or_default() as u16;
+
+	let mut new_glyf = Vec::new();
+	let mut new_loca = Vec::with_capacity((kept.len() + 1) * 4);
+	let mut new_hmtx = Vec::with_capacity(kept.len() * 4);
+	for &glyph in &kept {
+		new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+		let start = new_glyf.len();
+		new_glyf.extend_from_slice(glyph_data(glyph));
+		for (offset, component) in composite_components(glyph_data(glyph)) {
+			new_glyf[start + offset..start + offset + 2]
+				.copy_from_slice(&new_id(component).to_be_bytes());
+		}
+		new_glyf.resize(new_glyf.len().next_multiple_of(4), 0);
+
+		let id = ttf_parser::GlyphId(glyph);
+		new_hmtx.extend(face.glyph_hor_advance(id).unwrap_or(0).to_be_bytes());
+		new_hmtx.extend(face.glyph_hor_side_bearing(id).unwrap_or(0).to_be_bytes());
+	}
+	new_loca.extend((new_glyf.len() as u32).to_be_bytes());
+
+	let mut new_head = head.to_vec();
+	new_head[50..52].copy_from_slice(&1u16.to_be_bytes());
+	let mut new_hhea = hhea.to_vec();
+	new_hhea[34..36].copy_from_slice(&(kept.len() as u16).to_be_bytes());
+	let mut new_maxp = maxp.to_vec();
+	new_maxp[4..6].copy_from_slice(&(kept.len() as u16).to_be_bytes());
+	let mapping: Vec<(char, u16)> = chars
+		.iter()
+		.map(|(c, glyph)| (*c, new_id(*glyph)))
+		.collect();
+
+	let mut tables = vec![
+		(*b"glyf", new_glyf),
+		(*b"loca", new_loca),
+		(*b"hmtx", new_hmtx),
+		(*b"hhea", new_hhea),
+		(*b"maxp", new_maxp),
+		(*b"head", new_head),
+		(*b"cmap", build_cmap(&mapping)),
+	];
+	// post version 3 keeps the metrics header and drops the glyph names.
+	if let Some(post) = table(b"post").filter(|post| post.len() >= 32) {
+		let mut new_post = post[..32].to_vec();
+		new_post[0..4].copy_from_slice(&0x0003_0000u32.to_be_bytes());
+		tables.push((*b"post", new_post));
+	}
+	for tag in KEPT_TABLES {
+		if tables.iter().any(|(existing, _)| existing == tag) {
+			continue;
+		}
+		if let Some(data) = table(tag) {
+			tables.push((*tag, data.to_vec()));
+		}
+	}
+
+	Ok(write_sfnt(TRUETYPE_VERSION, tables))
+}
+
+// A Windows cmap with a format 4 subtable for BMP characters and a format 12
+// subtable for everything, one segment or group per character.
+fn build_cmap(mapping: &[(char, u16)]) -> Vec<u8> {
+	let bmp: Vec<(u16, u16)> = mapping
+		.iter()
+		.filter_map(|(c, glyph)| {
+			u16::try_from(*c as u32)
+				.ok()
+				.filter(|code| *code != 0xFFFF)
+				.map(|code| (code, *glyph))
+		})
+		.collect();
+	let seg_count = bmp.len() + 1;
+	let mut format4 = Vec::new();
+	if seg_count * 8 + 16 <= u16::MAX as usize {
+		let entry_selector = seg_count.ilog2() as u16;
+		let search_range = 2 << entry_selector;
+		format4.extend(4u16.to_be_bytes());
+		format4.extend(((seg_count * 8 + 16) as u16).to_be_bytes());
+		format4.extend(0u16.to_be_bytes());
+		for value in [
+			seg_count as u16 * 2,
+			search_range,
+			entry_selector,
+			seg_count as u16 * 2 - search_range,
+		] {
+			format4.extend(value.to_be_bytes());
+		}
+		let codes = bmp.iter().map(|(code, _)| *code).chain([0xFFFF]);
+		format4.extend(codes.clone().flat_map(u16::to_be_bytes));
+		format4.extend(0u16.to_be_bytes());
+		format4.extend(codes.flat_map(u16::to_be_bytes));
+		let deltas = bmp
+			.iter()
+			.map(|(code, glyph)| glyph.wrapping_sub(*code))
+			.chain([1]);
+		format4.extend(deltas.flat_map(u16::to_be_bytes));
+		format4.extend(std::iter::repeat_n(0u8, seg_count * 2));
+	}
+
+	let mut format12 = Vec::new();
+	format12.extend(12u16.to_be_bytes());
+	format12.extend(0u16.to_be_bytes());
+	format12.extend(((16 + mapping.len() * 12) as u32).to_be_bytes());
+	format12.extend(0u32.to_be_bytes());
+	format12.extend((mapping.len() as u32).to_be_bytes());
+	for (c, glyph) in mapping {
+		let code = *c as u32;
+		format12.extend(code.to_be_bytes());
+		format12.extend(code.to_be_bytes());
+		format12.extend((*glyph as u32).to_be_bytes());
+	}
+
+	let subtables: Vec<(u16, &[u8])> = [(1, format4.as_slice()), (10, format12.as_slice())]
+		.into_iter()
+		.filter(|(_, data)| !data.is_empty())
+		.collect();
+	let mut cmap = Vec::new();
+	cmap.extend(0u16.to_be_bytes());
+	cmap.extend((subtables.len() as u16).to_be_bytes());
+	let mut offset = 4 + subtables.len() * 8;
+	for (encoding, data) in &subtables {
+		cmap.extend(3u16.to_be_bytes());
+		cmap.extend(encoding.to_be_bytes());
+		cmap.extend((offset as u32).to_be_bytes());
+		offset += data.len();
+	}
+	for (_, data) in subtables {
+		cmap.extend(data);
+	}
+	cmap
+}
+
+// Component glyph ids of a composite glyph, with the byte offset each id is
+// stored at.
+fn composite_components(glyph: &[u8]) -> Vec<(usize, u16)> {
+	let mut components = Vec::new();
+	if !matches!(read_u16(glyph, 0), Some(contours) if (contours as i16) < 0) {
+		return components;
+	}
+
+	let mut cursor = 10;
+	while let (Some(flags), Some(component)) =
+		(read_u16(glyph, cursor), read_u16(glyph, cursor + 2))
+	{
+		components.push((cursor + 2, component));
+		cursor += 4;
+		cursor += if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+			4
+		} else {
+			2
+		};
+		if flags & WE_HAVE_A_SCALE != 0 {
+			cursor += 2;
+		} else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+			cursor += 4;
+		} else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+			cursor += 8;
+		}
+		if flags & MORE_COMPONENTS == 0 {
+			break;
+		}
+	}
+
+	components
+}
+
+// Lays the tables out as a single-font file; `head.checkSumAdjustment` is
+// recomputed.
+pub(crate) fn write_sfnt(version: u32, mut tables: Vec<([u8; 4], Vec<u8>)>) -> Vec<u8> {
+	tables.sort_by_key(|(tag, _)| *tag);
+	for (tag, data) in &mut tables {
+		if tag == b"head" && data.len() >= 12 {
+			data[8..12].fill(0);
+		}
+	}
+
+	let num_tables = tables.len() as u16;
+	let entry_selector = num_tables.max(1).ilog2() as u16;
+	let search_range = (1u16 << entry_selector) * 16;
+	let range_shift = num_tables * 16 - search_range;
+
+	let mut out = Vec::new();
+	out.extend(version.to_be_bytes());
+	for value in [num_tables, search_range, entry_selector, range_shift] {
+		out.extend(value.to_be_bytes());
+	}
+
+	let mut offset = 12 + tables.len() * 16;
+	for (tag, data) in &tables {
+		out.extend(tag);
+		out.extend(checksum(data).to_be_bytes());
+		out.extend((offset as u32).to_be_bytes());
+		out.extend((data.len() as u32).to_be_bytes());
+		offset += data.len().next_multiple_of(4);
+	}
+
+	let mut head_offset = None;
+	for (tag, data) in &tables {
+		if tag == b"head" {
+			head_offset = Some(out.len());
+		}
+		out.extend(data);
+		out.resize(out.len().next_multiple_of(4), 0);
+	}
+
+	if let Some(head_offset) = head_offset.filter(|offset| out.len() >= offset + 12) {
+		let adjustment = 0xB1B0_AFBAu32.wrapping_sub(checksum(&out));
+		out[head_offset + 8..head_offset + 12].copy_from_slice(&adjustment.to_be_bytes());
+	}
+
+	out
+}
+
+fn checksum(data: &[u8]) -> u32 {
+	data.chunks(4).fold(0u32, |sum, chunk| {
+		let mut word = [0u8; 4];
+		word[..chunk.len()].copy_from_slice(chunk);
+		sum.wrapping_add(u32::from_be_bytes(word))
+	})
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+	data.get(offset..offset + 2)
+		.map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+	data.get(offset..offset + 4)
+		.map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn invalid_font(reason: &str) -> GlyphWeaveError {
+	GlyphWeaveError::FontLoad(format!("failed to subset font: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sfnt_writer_produces_a_parseable_directory() {
+		let head = vec![0u8; 54];
+		let font = write_sfnt(
+			TRUETYPE_VERSION,
+			vec![(*b"name", vec![1, 2, 3]), (*b"head", head)],
+		);
+		assert_eq!(&font[0..4], &[0, 1, 0, 0]);
+		assert_eq!(read_u16(&font, 4), Some(2));
+		assert_eq!(&font[12..16], b"head");
+		assert_eq!(font.len() % 4, 0);
+		assert_eq!(checksum(&font), 0xB1B0_AFBA);
+	}
+}
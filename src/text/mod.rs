@@ -0,0 +1,222 @@
+use crate::core::error::GlyphWeaveError;
+use crate::core::model::{TextOptions, WordEntry};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+mod phrases;
+
+#[rustfmt::skip]
+pub const ENGLISH_STOP_WORDS: &[&str] = &[
+	"a", "about", "above", "after", "again", "against", "all", "also", "am", "an", "and", "any",
+	"are", "aren't", "as", "at", "be", "because", "been", "before", "being", "below", "between",
+	"both", "but", "by", "can", "can't", "cannot", "could", "couldn't", "did", "didn't", "do",
+	"does", "doesn't", "doing", "don't", "down", "during", "each", "else", "ever", "few", "for",
+	"from", "further", "get", "got", "had", "hadn't", "has", "hasn't", "have", "haven't",
+	"having", "he", "he'd", "he'll", "he's", "her", "here", "here's", "hers", "herself", "him",
+	"himself", "his", "how", "how's", "however", "i", "i'd", "i'll", "i'm", "i've", "if", "in",
+	"into", "is", "isn't", "it", "it's", "its", "itself", "just", "let's", "like", "may", "me",
+	"might", "more", "most", "much", "must", "mustn't", "my", "myself", "no", "nor", "not",
+	"now", "of", "off", "on", "once", "only", "or", "other", "ought", "our", "ours",
+	"ourselves", "out", "over", "own", "same", "shall", "shan't", "she", "she'd", "she'll",
+	"she's", "should", "shouldn't", "since", "so", "some", "such", "than", "that", "that's",
+	"the", "their", "theirs", "them", "themselves", "then", "there", "there's", "these", "they",
+	"they'd", "they'll", "they're", "they've", "this", "those", "through", "to", "too", "under",
+	"until", "up", "us", "very", "was", "wasn't", "we", "we'd", "we'll", "we're", "we've",
+	"were", "weren't", "what", "what's", "when", "when's", "where", "where's", "which", "while",
+	"who", "who's", "whom", "why", "why's", "will", "with", "won't", "would", "wouldn't", "yet",
+	"you", "you'd", "you'll", "you're", "you've", "your", "yours", "yourself", "yourselves",
+];
+
+// Multi-character words only; single characters are dropped during segmentation.
+#[cfg(feature = "cjk")]
+#[rustfmt::skip]
+pub const CHINESE_STOP_WORDS: &[&str] = &[
+	"我们", "你们", "他们", "她们", "它们", "一个", "一些", "没有", "不是", "因为", "所以",
+	"但是", "如果", "可以", "这个", "那个", "这些", "那些", "这样", "那样", "自己", "什么",
+	"已经", "还是", "以及", "进行", "通过", "对于", "其中", "之后", "之前", "以后", "就是",
+	"而且", "或者", "并且", "虽然", "然后", "他的", "我的", "你的", "这种", "一样", "时候",
+];
+
+const WORD_JOINERS: [char; 3] = ['\'', '\u{2019}', '-'];
+
+// Splits on anything that is not a letter, digit, apostrophe or hyphen, then
+// trims joiners from the edges so quotes and dashes never start a word.
+// With the `cjk` feature, runs containing Chinese or Japanese script are
+// further cut by a dictionary segmenter.
+pub fn tokenize(text: &str) -> Vec<&str> {
+	let mut tokens = Vec::new();
+	for token in text
+		.split(|c: char| !c.is_alphanumeric() && !WORD_JOINERS.contains(&c))
+		.map(|token| token.trim_matches(&WORD_JOINERS[..]))
+		.filter(|token| token.chars().any(char::is_alphabetic))
+	{
+		#[cfg(feature = "cjk")]
+		if token.chars().any(is_cjk) {
+			tokens.extend(cjk::segment(token));
+			continue;
+		}
+		tokens.push(token);
+	}
+	tokens
+}
+
+fn is_cjk(c: char) -> bool {
+	matches!(
+		c,
+		'\u{3040}'..='\u{30FF}'
+			| '\u{3400}'..='\u{4DBF}'
+			| '\u{4E00}'..='\u{9FFF}'
+			| '\u{F900}'..='\u{FAFF}'
+			| '\u{20000}'..='\u{2FA1F}'
+	)
+}
+
+#[cfg(feature = "cjk")]
+mod cjk {
+	use super::is_cjk;
+	use jieba_rs::Jieba;
+	use std::sync::OnceLock;
+
+	// Loading the bundled dictionary takes a moment, so it is done once per process.
+	fn segmenter() -> &'static Jieba {
+		static JIEBA: OnceLock<Jieba> = OnceLock::new();
+		JIEBA.get_or_init(Jieba::new)
+	}
+
+	pub(super) fn segment(run: &str) -> impl Iterator<Item = &str> {
+		segmenter().cut(run, true).into_iter().filter(|word| {
+			let mut chars = word.chars();
+			match (chars.next(), chars.next()) {
+				(Some(c), None) => !is_cjk(c) && c.is_alphabetic(),
+				(Some(_), Some(_)) => word.chars().any(char::is_alphabetic),
+				_ => false,
+			}
+		})
+	}
+}
+
+pub fn words_from_text(text: &str, options: &TextOptions) -> Vec<WordEntry> {
+	let runs = word_runs(text, options);
+	let counts = match &options.phrases {
+		Some(phrase_options) => phrases::count_with_phrases(&runs, phrase_options),
+		None => {
+			let mut counts: HashMap<String, usize> = HashMap::new();
+			for word in runs.into_iter().flatten() {
+				*counts.entry(word).or_insert(0) += 1;
+			}
+			counts
+		}
+	};
+
+	let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+	ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+	if let Some(max_words) = options.max_words {
+		ranked.truncate(max_words);
+	}
+
+	ranked
+		.into_iter()
+		.map(|(word, count)| WordEntry::new(word, count as f32))
+		.collect()
+}
+
+// Normalized words grouped into runs that never cross clause punctuation or a
+// removed stop word, so phrase detection only sees words that were adjacent.
+fn word_runs(text: &str, options: &TextOptions) -> Vec<Vec<String>> {
+	let mut stop_words: HashSet<String> = options
+		.stop_words
+		.iter()
+		.map(|word| word.trim().to_lowercase())
+		.collect();
+	if options.builtin_stop_words {
+		stop_words.extend(ENGLISH_STOP_WORDS.iter().map(|word| word.to_string()));
+		#[cfg(feature = "cjk")]
+		stop_words.extend(CHINESE_STOP_WORDS.iter().map(|word| word.to_string()));
+	}
+
+	let mut runs = vec![Vec::new()];
+	for clause in text.split(is_clause_break) {
+		for token in tokenize(clause) {
+			let token = token.replace('\u{2019}', "'");
+			let token = token
+				.strip_suffix("'s")
+				.filter(|stem| !stem.is_empty())
+				.unwrap_or(&token);
+			if stop_words.contains(&token.to_lowercase()) {
+				runs.push(Vec::new());
+				continue;
+			}
+
+			let word = if options.lowercase {
+				token.to_lowercase()
+			} else {
+				token.to_string()
+			};
+			runs.last_mut().expect("runs is never empty").push(word);
+		}
+		runs.push(Vec::new());
+	}
+
+	runs.retain(|run| !run.is_empty());
+	runs
+}
+
+fn is_clause_break(c: char) -> bool {
+	matches!(
+		c,
+		'.' | ','
+			| ';' | ':'
+			| '!' | '?'
+			| '(' | ')'
+			| '"' | '。'
+			| '，' | '、'
+			| '；' | '：'
+			| '！' | '？'
+	)
+}
+
+pub fn load_words_from_text_file(
+	path: &Path,
+	options: &TextOptions,
+) -> Result<Vec<WordEntry>, GlyphWeaveError> {
+	let text = std::fs::read_to_string(path)?;
+	Ok(words_from_text(&text, options))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn counts_words_without_stop_words_and_keeps_top_n() {
+		let text = "The cat's hat. The CAT sat; a dog-friendly cat, 'quoted' and 42 hats!";
+		let options = TextOptions {
+			stop_words: vec!["Sat".to_string()],
+			max_words: Some(3),
+			..TextOptions::default()
+		};
+
+		let words = words_from_text(text, &options);
+		let summary: Vec<(&str, f32)> = words.iter().map(|w| (w.text.as_str(), w.weight)).collect();
+		assert_eq!(
+			summary,
+			vec![("cat", 3.0), ("dog-friendly", 1.0), ("hat", 1.0)]
+		);
+	}
+
+	#[cfg(feature = "cjk")]
+	#[test]
+	fn segments_chinese_prose_into_dictionary_words() {
+		let text = "我们喜欢机器学习。机器学习的模型需要数据，数据很重要。";
+		let words = words_from_text(text, &TextOptions::default());
+		let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+
+		assert!(texts.contains(&"数据"), "{texts:?}");
+		assert!(
+			texts.iter().all(|word| word.chars().count() > 1),
+			"{texts:?}"
+		);
+		assert!(!texts.contains(&"我们"));
+		assert_eq!(words[0].weight, 2.0);
+	}
+}
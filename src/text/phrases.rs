@@ -0,0 +1,109 @@
+use super::is_cjk;
+use crate::core::model::PhraseOptions;
+use std::collections::{HashMap, HashSet};
+
+const MAX_PHRASE_LEN: usize = 3;
+
+// Counts words like `words_from_text` does, except that n-grams which repeat
+// at least `min_count` times with a PMI of at least `min_pmi` are counted as a
+// single phrase and no longer add to their constituent words.
+pub(super) fn count_with_phrases(
+	runs: &[Vec<String>],
+	options: &PhraseOptions,
+) -> HashMap<String, usize> {
+	let max_len = options.max_len.clamp(1, MAX_PHRASE_LEN);
+	let total: usize = runs.iter().map(Vec::len).sum();
+
+	let mut unigrams: HashMap<&str, usize> = HashMap::new();
+	let mut ngrams: HashMap<&[String], usize> = HashMap::new();
+	for run in runs {
+		for word in run {
+			*unigrams.entry(word.as_str()).or_insert(0) += 1;
+		}
+		for len in 2..=max_len {
+			for gram in run.windows(len) {
+				*ngrams.entry(gram).or_insert(0) += 1;
+			}
+		}
+	}
+
+	let accepted: HashSet<&[String]> = ngrams
+		.into_iter()
+		.filter(|&(gram, count)| {
+			count >= options.min_count.max(1)
+				&& pmi(gram, count, &unigrams, total) >= options.min_pmi
+		})
+		.map(|(gram, _)| gram)
+		.collect();
+
+	let mut counts: HashMap<String, usize> = HashMap::new();
+	for run in runs {
+		let mut start = 0;
+		while start < run.len() {
+			let len = (2..=max_len)
+				.rev()
+				.find(|&len| {
+					start + len <= run.len() && accepted.contains(&run[start..start + len])
+				})
+				.unwrap_or(1);
+			*counts
+				.entry(join_phrase(&run[start..start + len]))
+				.or_insert(0) += 1;
+			start += len;
+		}
+	}
+
+	counts
+}
+
+// Pointwise mutual information in bits: how much more often the words appear
+// together than independent draws from the corpus would predict.
+fn pmi(gram: &[String], count: usize, unigrams: &HashMap<&str, usize>, total: usize) -> f32 {
+	let total = total as f64;
+	let expected: f64 = gram
+		.iter()
+		.map(|word| unigrams[word.as_str()] as f64 / total)
+		.product();
+	((count as f64 / total) / expected).log2() as f32
+}
+
+fn join_phrase(words: &[String]) -> String {
+	let mut phrase = String::new();
+	for word in words {
+		let between_cjk = phrase.chars().next_back().is_some_and(is_cjk)
+			&& word.chars().next().is_some_and(is_cjk);
+		if !phrase.is_empty() && !between_cjk {
+			phrase.push(' ');
+		}
+		phrase.push_str(word);
+	}
+	phrase
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::core::model::{PhraseOptions, TextOptions};
+	use crate::text::words_from_text;
+
+	#[test]
+	fn frequent_collocations_become_single_words() {
+		let text = "Machine learning helps. We teach machine learning daily. \
+			Machine learning scales, the machine hums. Learning is fun. \
+			Data pipelines feed machine learning. Rust data, rust speed.";
+		let options = TextOptions {
+			phrases: Some(PhraseOptions {
+				min_count: 3,
+				min_pmi: 1.0,
+				..PhraseOptions::default()
+			}),
+			..TextOptions::default()
+		};
+
+		let words = words_from_text(text, &options);
+		let weight = |text: &str| words.iter().find(|w| w.text == text).map(|w| w.weight);
+		assert_eq!(weight("machine learning"), Some(4.0));
+		assert_eq!(weight("machine"), Some(1.0));
+		assert_eq!(weight("learning"), Some(1.0));
+		assert_eq!(weight("rust data"), None);
+	}
+}
@@ -0,0 +1,148 @@
+//! Single-line glyph layout shared by shape masks, word footprints and every
+//! renderer, so the box a word reserves is the box its ink is drawn in.
+
+use crate::font::FontChain;
+use fontdue::Metrics;
+use ndarray::Array2;
+
+/// A glyph positioned on the line. `x` is the pen position of its origin,
+/// kerning included, relative to the start of the line on the baseline.
+/// `font` indexes the face of the chain that draws it.
+#[derive(Debug, Clone, Copy)]
+pub struct PlacedGlyph {
+	pub ch: char,
+	pub font: usize,
+	pub x: f32,
+	pub metrics: Metrics,
+}
+
+/// A laid-out line of text plus the pixel bounds of its ink. Bounds are
+/// relative to the line start on the baseline, with y pointing up like font
+/// units: `top` is the highest ink row, `bottom` the lowest (negative for
+/// descenders).
+#[derive(Debug, Clone)]
+pub struct LineLayout {
+	pub font_size: f32,
+	pub glyphs: Vec<PlacedGlyph>,
+	pub advance: f32,
+	pub left: i32,
+	pub right: i32,
+	pub top: i32,
+	pub bottom: i32,
+}
+
+impl LineLayout {
+	pub fn new(text: &str, fonts: &FontChain, font_size: f32) -> Self {
+		let mut glyphs = Vec::with_capacity(text.len());
+		let mut pen = 0.0f32;
+		let mut previous = None;
+		for ch in text.chars() {
+			let index = fonts.resolve(ch);
+			let font = fonts.face(index);
+			// Kerning pairs only exist within one face.
+			if let Some(kern) = previous
+				.filter(|(_, previous_index)| *previous_index == index)
+				.and_then(|(left, _)| font.horizontal_kern(left, ch, font_size))
+			{
+				pen += kern;
+			}
+			let metrics = font.metrics(ch, font_size);
+			glyphs.push(PlacedGlyph {
+				ch,
+				font: index,
+				x: pen,
+				metrics,
+			});
+			pen += metrics.advance_width;
+			previous = Some((ch, index));
+		}
+
+		let (mut left, mut right, mut top, mut bottom) = (i32::MAX, i32::MIN, i32::MIN, i32::MAX);
+		for glyph in glyphs.iter().filter(|g| has_ink(&g.metrics)) {
+			let glyph_left = glyph_left(glyph);
+			left = left.min(glyph_left);
+			right = right.max(glyph_left + glyph.metrics.width as i32);
+			top = top.max(glyph.metrics.ymin + glyph.metrics.height as i32);
+			bottom = bottom.min(glyph.metrics.ymin);
+		}
+		if left > right {
+			// Blank lines have no ink; keep their bounds empty at the origin.
+			(left, right, top, bottom) = (0, 0, 0, 0);
+		}
+
+		Self {
+			font_size,
+			glyphs,
+			advance: pen,
+			left,
+			right,
+			top,
+			bottom,
+		}
+	}
+
+	pub fn ink_width(&self) -> usize {
+		(self.right - self.left).max(0) as usize
+	}
+
+	pub fn ink_height(&self) -> usize {
+		(self.top - self.bottom).max(0) as usize
+	}
+
+	/// Offset from the ink centre to the line start on the baseline, in y-down
+	/// canvas pixels. Renderers add it to a box centre to find where to draw.
+	pub fn origin_from_center(&self) -> (f32, f32) {
+		(
+			-(self.left + self.right) as f32 / 2.0,
+			(self.top + self.bottom) as f32 / 2.0,
+		)
+	}
+
+	/// Glyph coverage (0-255) cropped to the ink bounds.
+	pub fn coverage(&self, fonts: &FontChain) -> Array2<u8> {
+		let mut coverage = Array2::<u8>::zeros((self.ink_height(), self.ink_width()));
+		for glyph in self.glyphs.iter().filter(|g| has_ink(&g.metrics)) {
+			let (metrics, bitmap) = fonts.face(glyph.font).rasterize(glyph.ch, self.font_size);
+			let left = (glyph_left(glyph) - self.left) as usize;
+			let row = (self.top - (metrics.ymin + metrics.height as i32)) as usize;
+			for y in 0..metrics.height {
+				for x in 0..metrics.width {
+					let cell = &mut coverage[[row + y, left + x]];
+					*cell = (*cell).max(bitmap[y * metrics.width + x]);
+				}
+			}
+		}
+		coverage
+	}
+}
+
+fn has_ink(metrics: &Metrics) -> bool {
+	metrics.width > 0 && metrics.height > 0
+}
+
+fn glyph_left(glyph: &PlacedGlyph) -> i32 {
+	(glyph.x + glyph.metrics.xmin as f32).round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+	#[cfg(feature = "embedded_fonts")]
+	#[test]
+	fn line_bounds_cover_descenders_and_match_coverage() {
+		use super::*;
+
+		let font = FontChain::from(
+			crate::font::load_default_embedded_font().expect("embedded font should load"),
+		);
+		let line = LineLayout::new("gy", &font, 48.0);
+		assert!(line.bottom < 0 && line.top > 0);
+		assert_eq!(
+			line.coverage(&font).dim(),
+			(line.ink_height(), line.ink_width())
+		);
+
+		let (_, dy) = line.origin_from_center();
+		assert!(dy < line.ink_height() as f32 / 2.0);
+		assert!(LineLayout::new("", &font, 48.0).coverage(&font).is_empty());
+	}
+}
@@ -0,0 +1,649 @@
+//! WOFF and WOFF2 decoding. Both wrap an ordinary sfnt, which is rebuilt here
+//! so the parser, outline renderer and subsetter only ever see TrueType or
+//! OpenType data.
+
+use crate::subset::write_sfnt;
+use std::io::Read;
+
+const WOFF_SIGNATURE: &[u8; 4] = b"wOFF";
+const WOFF2_SIGNATURE: &[u8; 4] = b"wOF2";
+const COLLECTION_FLAVOR: u32 = u32::from_be_bytes(*b"ttcf");
+
+// Tags WOFF2 encodes as a 6-bit index instead of spelling them out.
+const KNOWN_TAGS: [&[u8; 4]; 63] = [
+	b"cmap", b"head", b"hhea", b"hmtx", b"maxp", b"name", b"OS/2", b"post", b"cvt ", b"fpgm",
+	b"glyf", b"loca", b"prep", b"CFF ", b"VORG", b"EBDT", b"EBLC", b"gasp", b"hdmx", b"kern",
+	b"LTSH", b"PCLT", b"VDMX", b"vhea", b"vmtx", b"BASE", b"GDEF", b"GPOS", b"GSUB", b"EBSC",
+	b"JSTF", b"MATH", b"CBDT", b"CBLC", b"COLR", b"CPAL", b"SVG ", b"sbix", b"acnt", b"avar",
+	b"bdat", b"bloc", b"bsln", b"cvar", b"fdsc", b"feat", b"fmtx", b"fvar", b"gvar", b"hsty",
+	b"just", b"lcar", b"mort", b"morx", b"opbd", b"prop", b"trak", b"Zapf", b"Silf", b"Glat",
+	b"Gloc", b"Feat", b"Sill",
+];
+
+const ON_CURVE_POINT: u8 = 0x01;
+const X_SHORT_VECTOR: u8 = 0x02;
+const Y_SHORT_VECTOR: u8 = 0x04;
+const REPEAT_FLAG: u8 = 0x08;
+const X_IS_SAME_OR_POSITIVE: u8 = 0x10;
+const Y_IS_SAME_OR_POSITIVE: u8 = 0x20;
+const OVERLAP_SIMPLE: u8 = 0x40;
+const ARG_1_AND_2_ARE_WORDS: u16 = 0x0001;
+const WE_HAVE_A_SCALE: u16 = 0x0008;
+const MORE_COMPONENTS: u16 = 0x0020;
+const WE_HAVE_AN_X_AND_Y_SCALE: u16 = 0x0040;
+const WE_HAVE_A_TWO_BY_TWO: u16 = 0x0080;
+const WE_HAVE_INSTRUCTIONS: u16 = 0x0100;
+
+pub fn is_woff(data: &[u8]) -> bool {
+	data.starts_with(WOFF_SIGNATURE) || data.starts_with(WOFF2_SIGNATURE)
+}
+
+/// Unwraps WOFF or WOFF2 data into a plain sfnt.
+pub fn decode_woff(data: &[u8]) -> Result<Vec<u8>, String> {
+	if data.starts_with(WOFF_SIGNATURE) {
+		decode_woff1(data)
+	} else if data.starts_with(WOFF2_SIGNATURE) {
+		decode_woff2(data)
+	} else {
+		Err("not a WOFF or WOFF2 file".to_string())
+	}
+}
+
+fn decode_woff1(data: &[u8]) -> Result<Vec<u8>, String> {
+	let mut header = Stream::at(data, 4);
+	let flavor = header.u32()?;
+	header.skip(4)?;
+	let num_tables = header.u16()?;
+
+	let mut directory = Stream::at(data, 44);
+	let mut tables = Vec::with_capacity(num_tables as usize);
+	for _ in 0..num_tables {
+		let tag = directory.tag()?;
+		let offset = directory.u32()? as usize;
+		let compressed_length = directory.u32()? as usize;
+		let length = directory.u32()? as usize;
+		directory.skip(4)?;
+
+		let stored = data
+			.get(offset..offset + compressed_length)
+			.ok_or("WOFF table points outside the file")?;
+		let table = if compressed_length < length {
+			let mut table = Vec::with_capacity(length);
+			flate2::read::ZlibDecoder::new(stored)
+				.read_to_end(&mut table)
+				.map_err(|err| format!("failed to inflate WOFF table: {err}"))?;
+			table
+		} else {
+			stored.to_vec()
+		};
+		if table.len() != length {
+			return Err("WOFF table has the wrong decompressed size".to_string());
+		}
+		tables.push((tag, table));
+	}
+
+	Ok(write_sfnt(flavor, tables))
+}
+
+struct Woff2Table {
+	tag: [u8; 4],
+	transform: u8,
+	length: usize,
+	stored_length: usize,
+}
+
+impl Woff2Table {
+	fn is_transformed(&self) -> bool {
+		match &self.tag {
+			// For glyf and loca, version 3 is the null transform.
+			b"glyf" | b"loca" => self.transform != 3,
+			_ => self.transform != 0,
+		}
+	}
+}
+
+fn decode_woff2(data: &[u8]) -> Result<Vec<u8>, String> {
+	let mut header = Stream::at(data, 4);
+	let flavor = header.u32()?;
+	header.skip(4)?;
+	let num_tables = header.u16()?;
+	header.skip(6)?;
+	let compressed_size = header.u32()? as usize;
+	if flavor == COLLECTION_FLAVOR {
+		return Err("WOFF2 font collections are not supported".to_string());
+	}
+
+	let mut directory = Stream::at(data, 48);
+	let mut entries = Vec::with_capacity(num_tables as usize);
+	for _ in 0..num_tables {
+		let flags = directory.u8()?;
+		let tag = match flags & 0x3f {
+			63 => directory.tag()?,
+			index => *KNOWN_TAGS[index as usize],
+		};
+		let mut table = Woff2Table {
+			tag,
+			transform: flags >> 6,
+			length: directory.base128()? as usize,
+			stored_length: 0,
+		};
+		table.stored_length = if table.is_transformed() {
+			directory.base128()? as usize
+		} else {
+			table.length
+		};
+		entries.push(table);
+	}
+
+	let compressed = data
+		.get(directory.pos..directory.pos + compressed_size)
+		.ok_or("WOFF2 data is truncated")?;
+	let mut stream = Vec::new();
+	brotli_decompressor::Decompressor::new(compressed, 4096)
+		.read_to_end(&mut stream)
+		.map_err(|err| format!("failed to decompress WOFF2 data: {err}"))?;
+
+	let mut offset = 0;
+	let mut raw = Vec::with_capacity(entries.len());
+	for entry in &entries {
+		let table = stream
+			.get(offset..offset + entry.stored_length)
+			.ok_or("WOFF2 table points outside the data")?;
+		raw.push(table);
+		offset += entry.stored_length;
+	}
+	let find = |tag: &[u8; 4]| entries.iter().position(|entry| &entry.tag == tag);
+
+	let mut glyf_and_loca = None;
+	if let Some(glyf) = find(b"glyf").filter(|index| entries[*index].is_transformed()) {
+		glyf_and_loca = Some(reconstruct_glyf(raw[glyf])?);
+	}
+
+	let mut tables = Vec::with_capacity(entries.len());
+	for (entry, table) in entries.iter().zip(&raw) {
+		let table = match (&entry.tag, &glyf_and_loca) {
+			(b"glyf", Some(glyph_tables)) => glyph_tables.glyf.clone(),
+			(b"loca", Some(glyph_tables)) => glyph_tables.loca.clone(),
+			(b"hmtx", _) if entry.is_transformed() => {
+				let glyph_tables = glyf_and_loca
+					.as_ref()
+					.ok_or("transformed hmtx needs a transformed glyf table")?;
+				let hhea = find(b"hhea")
+					.map(|index| raw[index])
+					.ok_or("transformed hmtx needs an hhea table")?;
+				reconstruct_hmtx(table, hhea, &glyph_tables.x_mins)?
+			}
+			_ if entry.is_transformed() => {
+				return Err(format!(
+					"unsupported WOFF2 transform for table '{}'",
+					String::from_utf8_lossy(&entry.tag)
+				));
+			}
+			(b"head", Some(_)) => {
+				let mut head = table.to_vec();
+				head.get_mut(50..52)
+					.ok_or("head table is truncated")?
+					.copy_from_slice(&1u16.to_be_bytes());
+				head
+			}
+			_ => table.to_vec(),
+		};
+		tables.push((entry.tag, table));
+	}
+
+	Ok(write_sfnt(flavor, tables))
+}
+
+struct GlyphTables {
+	glyf: Vec<u8>,
+	loca: Vec<u8>,
+	// Per-glyph xMin, which a transformed hmtx uses as the left side bearing.
+	x_mins: Vec<i16>,
+}
+
+// Rebuilds glyf and loca from the WOFF2 split-stream encoding (transform 0).
+fn reconstruct_glyf(table: &[u8]) -> Result<GlyphTables, String> {
+	let mut header = Stream::at(table, 2);
+	let option_flags = header.u16()?;
+	let num_glyphs = header.u16()? as usize;
+	header.skip(2)?;
+	let mut sizes = [0usize; 7];
+	for size in &mut sizes {
+		*size = header.u32()? as usize;
+	}
+
+	let mut start = header.pos;
+	let streams = sizes.map(|size| {
+		let stream = table.get(start..start + size).unwrap_or_default();
+		let complete = stream.len() == size;
+		start += size;
+		(Stream::new(stream), complete)
+	});
+	if streams.iter().any(|(_, complete)| !complete) {
+		return Err("WOFF2 glyf streams are truncated".to_string());
+	}
+	let overlap_bitmap = if option_flags & 1 != 0 {
+		table
+			.get(start..start + num_glyphs.div_ceil(8))
+			.ok_or("WOFF2 overlap bitmap is truncated")?
+	} else {
+		&[]
+	};
+	let [
+		(mut contours, _),
+		(mut points, _),
+		(mut flags, _),
+		(mut glyphs, _),
+		(mut composites, _),
+		(mut bboxes, _),
+		(mut instructions, _),
+	] = streams;
+	let bbox_bitmap = bboxes.take(num_glyphs.div_ceil(32) * 4)?;
+	let has_bit = |bitmap: &[u8], glyph: usize| {
+		bitmap
+			.get(glyph >> 3)
+			.is_some_and(|byte| byte & (0x80 >> (glyph & 7)) != 0)
+	};
+
+	let mut glyf = Vec::new();
+	let mut offsets = Vec::with_capacity(num_glyphs + 1);
+	let mut x_mins = Vec::with_capacity(num_glyphs);
+	for glyph in 0..num_glyphs {
+		offsets.push(glyf.len());
+		let contour_count = contours.u16()? as i16;
+		let explicit_bbox = if has_bit(bbox_bitmap, glyph) {
+			Some([bboxes.i16()?, bboxes.i16()?, bboxes.i16()?, bboxes.i16()?])
+		} else {
+			None
+		};
+
+		if contour_count == 0 {
+			if explicit_bbox.is_some() {
+				return Err("empty WOFF2 glyph has a bounding box".to_string());
+			}
+			x_mins.push(0);
+			continue;
+		}
+
+		if contour_count < 0 {
+			let bbox = explicit_bbox.ok_or("composite WOFF2 glyph has no bounding box")?;
+			let (components, has_instructions) = read_composite(&mut composites)?;
+			write_i16s(&mut glyf, &[-1, bbox[0], bbox[1], bbox[2], bbox[3]]);
+			glyf.extend_from_slice(components);
+			if has_instructions {
+				let length = glyphs.u255()?;
+				glyf.extend(length.to_be_bytes());
+				glyf.extend_from_slice(instructions.take(length as usize)?);
+			}
+			x_mins.push(bbox[0]);
+		} else {
+			let mut end_points = Vec::with_capacity(contour_count as usize);
+			let mut total = 0usize;
+			for _ in 0..contour_count {
+				total += points.u255()? as usize;
+				let end = total.checked_sub(1).ok_or("WOFF2 contour has no points")?;
+				end_points.push(end as u16);
+			}
+
+			let mut outline = Vec::with_capacity(total);
+			let (mut x, mut y) = (0i32, 0i32);
+			let (mut min_x, mut min_y, mut max_x, mut max_y) =
+				(i32::MAX, i32::MAX, i32::MIN, i32::MIN);
+			for _ in 0..total {
+				let (on_curve, dx, dy) = read_triplet(flags.u8()?, &mut glyphs)?;
+				(x, y) = (x + dx, y + dy);
+				(min_x, min_y) = (min_x.min(x), min_y.min(y));
+				(max_x, max_y) = (max_x.max(x), max_y.max(y));
+				outline.push((on_curve, dx, dy));
+			}
+			let bbox =
+				explicit_bbox.unwrap_or([min_x as i16, min_y as i16, max_x as i16, max_y as i16]);
+			let instruction_length = glyphs.u255()?;
+
+			write_i16s(
+				&mut glyf,
+				&[contour_count, bbox[0], bbox[1], bbox[2], bbox[3]],
+			);
+			for end in end_points {
+				glyf.extend(end.to_be_bytes());
+			}
+			glyf.extend(instruction_length.to_be_bytes());
+			glyf.extend_from_slice(instructions.take(instruction_length as usize)?);
+			write_points(&mut glyf, &outline, has_bit(overlap_bitmap, glyph));
+			x_mins.push(bbox[0]);
+		}
+		glyf.resize(glyf.len().next_multiple_of(4), 0);
+	}
+	offsets.push(glyf.len());
+
+	// The rebuilt glyf can outgrow what short offsets address, so loca is
+	// always written long and `head.indexToLocFormat` patched to match.
+	let mut loca = Vec::with_capacity(offsets.len() * 4);
+	for offset in offsets {
+		loca.extend((offset as u32).to_be_bytes());
+	}
+
+	Ok(GlyphTables { glyf, loca, x_mins })
+}
+
+// Writes simple-glyph flags and coordinates the way font compilers do: deltas
+// below 256 take one byte, zero deltas none, and runs of equal flags collapse
+// into a repeat count.
+fn write_points(glyf: &mut Vec<u8>, outline: &[(bool, i32, i32)], overlap: bool) {
+	let mut xs = Vec::new();
+	let mut ys = Vec::new();
+	let mut flags = Vec::with_capacity(outline.len());
+	for (index, &(on_curve, dx, dy)) in outline.iter().enumerate() {
+		let mut flag = if on_curve { ON_CURVE_POINT } else { 0 };
+		if index == 0 && overlap {
+			flag |= OVERLAP_SIMPLE;
+		}
+		flag |= encode_delta(&mut xs, dx, X_SHORT_VECTOR, X_IS_SAME_OR_POSITIVE);
+		flag |= encode_delta(&mut ys, dy, Y_SHORT_VECTOR, Y_IS_SAME_OR_POSITIVE);
+		flags.push(flag);
+	}
+
+	let mut index = 0;
+	while index < flags.len() {
+		let flag = flags[index];
+		let run = flags[index + 1..]
+			.iter()
+			.take(255)
+			.take_while(|next| **next == flag)
+			.count();
+		if run > 0 {
+			glyf.extend([flag | REPEAT_FLAG, run as u8]);
+		} else {
+			glyf.push(flag);
+		}
+		index += run + 1;
+	}
+	glyf.extend(xs);
+	glyf.extend(ys);
+}
+
+fn encode_delta(out: &mut Vec<u8>, delta: i32, short: u8, same_or_positive: u8) -> u8 {
+	if delta == 0 {
+		same_or_positive
+	} else if delta.unsigned_abs() < 256 {
+		out.push(delta.unsigned_abs() as u8);
+		if delta > 0 {
+			short | same_or_positive
+		} else {
+			short
+		}
+	} else {
+		out.extend((delta as i16).to_be_bytes());
+		0
+	}
+}
+
+// Returns the raw component records and whether instructions follow them.
+fn read_composite<'a>(stream: &mut Stream<'a>) -> Result<(&'a [u8], bool), String> {
+	let start = stream.pos;
+	let mut has_instructions = false;
+	loop {
+		let flags = stream.u16()?;
+		stream.skip(2)?;
+		let mut length = if flags & ARG_1_AND_2_ARE_WORDS != 0 {
+			4
+		} else {
+			2
+		};
+		if flags & WE_HAVE_A_SCALE != 0 {
+			length += 2;
+		} else if flags & WE_HAVE_AN_X_AND_Y_SCALE != 0 {
+			length += 4;
+		} else if flags & WE_HAVE_A_TWO_BY_TWO != 0 {
+			length += 8;
+		}
+		stream.skip(length)?;
+		has_instructions |= flags & WE_HAVE_INSTRUCTIONS != 0;
+		if flags & MORE_COMPONENTS == 0 {
+			break;
+		}
+	}
+	Ok((&stream.data[start..stream.pos], has_instructions))
+}
+
+// Decodes one point of a WOFF2 simple glyph: on-curve flag plus x/y deltas.
+fn read_triplet(flag: u8, stream: &mut Stream) -> Result<(bool, i32, i32), String> {
+	let on_curve = flag & 0x80 == 0;
+	let flag = i32::from(flag & 0x7f);
+	let with_sign = |flag: i32, value: i32| if flag & 1 != 0 { value } else { -value };
+
+	let (dx, dy) = if flag < 10 {
+		let b0 = i32::from(stream.u8()?);
+		(0, with_sign(flag, ((flag & 14) << 7) + b0))
+	} else if flag < 20 {
+		let b0 = i32::from(stream.u8()?);
+		(with_sign(flag, (((flag - 10) & 14) << 7) + b0), 0)
+	} else if flag < 84 {
+		let (base, b0) = (flag - 20, i32::from(stream.u8()?));
+		(
+			with_sign(flag, 1 + (base & 0x30) + (b0 >> 4)),
+			with_sign(flag >> 1, 1 + ((base & 0x0c) << 2) + (b0 & 0x0f)),
+		)
+	} else if flag < 120 {
+		let base = flag - 84;
+		let (b0, b1) = (i32::from(stream.u8()?), i32::from(stream.u8()?));
+		(
+			with_sign(flag, 1 + ((base / 12) << 8) + b0),
+			with_sign(flag >> 1, 1 + (((base % 12) >> 2) << 8) + b1),
+		)
+	} else if flag < 124 {
+		let (b0, b1, b2) = (
+			i32::from(stream.u8()?),
+			i32::from(stream.u8()?),
+			i32::from(stream.u8()?),
+		);
+		(
+			with_sign(flag, (b0 << 4) + (b1 >> 4)),
+			with_sign(flag >> 1, ((b1 & 0x0f) << 8) + b2),
+		)
+	} else {
+		let (x, y) = (i32::from(stream.u16()?), i32::from(stream.u16()?));
+		(with_sign(flag, x), with_sign(flag >> 1, y))
+	};
+	Ok((on_curve, dx, dy))
+}
+
+// Restores left side bearings dropped by the hmtx transform from glyph xMin.
+fn reconstruct_hmtx(table: &[u8], hhea: &[u8], x_mins: &[i16]) -> Result<Vec<u8>, String> {
+	let metric_count = Stream::at(hhea, 34).u16()? as usize;
+	let num_glyphs = x_mins.len();
+	if metric_count == 0 || metric_count > num_glyphs {
+		return Err("hhea has an invalid number of horizontal metrics".to_string());
+	}
+
+	let mut stream = Stream::new(table);
+	let flags = stream.u8()?;
+	let mut advances = Vec::with_capacity(metric_count);
+	for _ in 0..metric_count {
+		advances.push(stream.u16()?);
+	}
+	let mut bearings = Vec::with_capacity(num_glyphs);
+	for (glyph, &x_min) in x_mins.iter().enumerate() {
+		let stored = if glyph < metric_count {
+			flags & 1 == 0
+		} else {
+			flags & 2 == 0
+		};
+		bearings.push(if stored { stream.i16()? } else { x_min });
+	}
+
+	let mut hmtx = Vec::with_capacity(metric_count * 4 + (num_glyphs - metric_count) * 2);
+	for (glyph, bearing) in bearings.iter().enumerate() {
+		if let Some(advance) = advances.get(glyph) {
+			hmtx.extend(advance.to_be_bytes());
+		}
+		hmtx.extend(bearing.to_be_bytes());
+	}
+	Ok(hmtx)
+}
+
+fn write_i16s(out: &mut Vec<u8>, values: &[i16]) {
+	for value in values {
+		out.extend(value.to_be_bytes());
+	}
+}
+
+struct Stream<'a> {
+	data: &'a [u8],
+	pos: usize,
+}
+
+impl<'a> Stream<'a> {
+	fn new(data: &'a [u8]) -> Self {
+		Self::at(data, 0)
+	}
+
+	fn at(data: &'a [u8], pos: usize) -> Self {
+		Self { data, pos }
+	}
+
+	fn take(&mut self, length: usize) -> Result<&'a [u8], String> {
+		let bytes = self
+			.data
+			.get(self.pos..self.pos + length)
+			.ok_or("font data is truncated")?;
+		self.pos += length;
+		Ok(bytes)
+	}
+
+	fn skip(&mut self, length: usize) -> Result<(), String> {
+		self.take(length).map(|_| ())
+	}
+
+	fn u8(&mut self) -> Result<u8, String> {
+		Ok(self.take(1)?[0])
+	}
+
+	fn u16(&mut self) -> Result<u16, String> {
+		let bytes = self.take(2)?;
+		Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn i16(&mut self) -> Result<i16, String> {
+		Ok(self.u16()? as i16)
+	}
+
+	fn u32(&mut self) -> Result<u32, String> {
+		let bytes = self.take(4)?;
+		Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn tag(&mut self) -> Result<[u8; 4], String> {
+		Ok(self.u32()?.to_be_bytes())
+	}
+
+	// UIntBase128: big-endian groups of 7 bits, high bit set on all but the last.
+	fn base128(&mut self) -> Result<u32, String> {
+		let mut value = 0u32;
+		for index in 0..5 {
+			let byte = self.u8()?;
+			if index == 0 && byte == 0x80 {
+				return Err("UIntBase128 value has a leading zero".to_string());
+			}
+			if value & 0xFE00_0000 != 0 {
+				return Err("UIntBase128 value overflows".to_string());
+			}
+			value = (value << 7) | u32::from(byte & 0x7f);
+			if byte & 0x80 == 0 {
+				return Ok(value);
+			}
+		}
+		Err("UIntBase128 value is too long".to_string())
+	}
+
+	// 255UInt16: one byte for small values, escape codes for larger ones.
+	fn u255(&mut self) -> Result<u16, String> {
+		match self.u8()? {
+			253 => self.u16(),
+			254 => Ok(506 + u16::from(self.u8()?)),
+			255 => Ok(253 + u16::from(self.u8()?)),
+			code => Ok(u16::from(code)),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::io::Write;
+
+	#[test]
+	fn variable_length_integers_decode() {
+		let mut stream = Stream::new(&[0x3f, 0x81, 0x00, 0x80]);
+		assert_eq!(stream.base128(), Ok(63));
+		assert_eq!(stream.base128(), Ok(128));
+		assert!(stream.base128().is_err());
+
+		let mut stream = Stream::new(&[252, 255, 0, 254, 4, 253, 0x12, 0x34]);
+		assert_eq!(stream.u255(), Ok(252));
+		assert_eq!(stream.u255(), Ok(253));
+		assert_eq!(stream.u255(), Ok(510));
+		assert_eq!(stream.u255(), Ok(0x1234));
+	}
+
+	#[test]
+	fn triplets_decode_signed_deltas() {
+		assert_eq!(read_triplet(1, &mut Stream::new(&[5])), Ok((true, 0, 5)));
+		assert_eq!(
+			read_triplet(0x8a, &mut Stream::new(&[7])),
+			Ok((false, -7, 0))
+		);
+		assert_eq!(
+			read_triplet(22, &mut Stream::new(&[0x21])),
+			Ok((true, -3, 2))
+		);
+		assert_eq!(
+			read_triplet(127, &mut Stream::new(&[0x01, 0x00, 0x02, 0x00])),
+			Ok((true, 256, 512))
+		);
+	}
+
+	#[test]
+	fn woff1_tables_are_inflated_into_an_sfnt() {
+		let name = b"glyphweave glyphweave glyphweave".to_vec();
+		let mut encoder =
+			flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder
+			.write_all(&name)
+			.expect("compression should succeed");
+		let compressed = encoder.finish().expect("compression should succeed");
+		let head = vec![0u8; 54];
+
+		let mut woff = Vec::new();
+		woff.extend(WOFF_SIGNATURE);
+		woff.extend(0x0001_0000u32.to_be_bytes());
+		woff.extend([0u8; 4]);
+		woff.extend(2u16.to_be_bytes());
+		woff.resize(44, 0);
+		let data_start = 44 + 2 * 20;
+		let tables = [
+			(b"head", head.as_slice(), head.len()),
+			(b"name", compressed.as_slice(), name.len()),
+		];
+		let mut offset = data_start;
+		for (tag, stored, length) in tables {
+			woff.extend(tag);
+			woff.extend((offset as u32).to_be_bytes());
+			woff.extend((stored.len() as u32).to_be_bytes());
+			woff.extend((length as u32).to_be_bytes());
+			woff.extend([0u8; 4]);
+			offset += stored.len();
+		}
+		for (_, stored, _) in tables {
+			woff.extend(stored);
+		}
+
+		let sfnt = decode_woff(&woff).expect("WOFF should decode");
+		assert_eq!(&sfnt[0..4], &[0, 1, 0, 0]);
+		assert!(
+			sfnt.windows(name.len())
+				.any(|window| window == name.as_slice())
+		);
+		assert!(decode_woff(b"wOFFtruncated").is_err());
+	}
+}
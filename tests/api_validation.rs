@@ -1,15 +1,53 @@
+mod support;
+
+use glyphweave::export::{layout_from_json, layout_to_json};
+use glyphweave::font::{css_family_name, font_family_name};
+use glyphweave::mask::{rasterize_text_coverage, resolve_shape_mask};
+use glyphweave::render::{render_layout_svg, render_raster};
+use glyphweave::subset::subset_font;
+use glyphweave::text_layout::LineLayout;
 use glyphweave::{
-	AlgorithmKind, CanvasConfig, CloudRequest, FontSizeSpec, RenderOptions, Rotation, ShapeConfig,
-	StyleConfig, WordEntry, generate, load_default_embedded_font, load_font_from_file,
-	rotations_from_degrees,
+	AlgorithmKind, CanvasConfig, CloudLayout, CloudRequest, CloudRequestSpec, CloudResult,
+	CollisionMode, ColorStrategy, FontChain, FontFace, FontSizeSpec, FontSource, PlacementMode,
+	RasterOptions, RenderOptions, Rotation, ShapeConfig, ShapeLayer, ShapeOp, ShapeRegion,
+	ShapeSource, SizeScaling, StyleConfig, SvgMaskSource, SvgTextMode, WordEntry, generate,
+	load_default_embedded_font, load_font_from_file, rotations_from_degrees,
 };
 use std::path::Path;
 use std::sync::Arc;
+use support::build_request;
 
 #[test]
 fn rejects_unsupported_rotation() {
-	let err = rotations_from_degrees(&[45]).expect_err("rotation should be rejected");
+	let err = rotations_from_degrees(&[720]).expect_err("rotation should be rejected");
 	assert!(err.to_string().contains("unsupported rotation"));
+	for extreme in [i16::MIN, i16::MAX] {
+		assert!(rotations_from_degrees(&[extreme]).is_err());
+	}
+
+	let rotations = rotations_from_degrees(&[-45, 30, 315]).expect("angles should be accepted");
+	assert_eq!(
+		rotations.iter().map(|r| r.degrees()).collect::<Vec<_>>(),
+		vec![-45, 30]
+	);
+}
+
+#[test]
+fn rejects_rotation_ranges_beyond_a_full_turn() {
+	for range in [i16::MIN..=0, 0..=i16::MAX, -400..=0] {
+		let mut request = build_request(AlgorithmKind::FastGrid);
+		request.style.rotation_range = Some(range);
+		let err = generate(request).expect_err("range should be rejected");
+		assert!(err.to_string().contains("rotation_range"));
+	}
+}
+
+#[test]
+fn image_colors_need_a_picture() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.style.color_strategy = ColorStrategy::Image { path: None };
+	let err = generate(request).expect_err("text shape has no picture to sample");
+	assert!(err.to_string().contains("color image"));
 }
 
 #[test]
@@ -25,10 +63,7 @@ fn generate_with_same_seed_is_stable() {
 			height: 260,
 			margin: 8,
 		},
-		shape: ShapeConfig {
-			text: "AI".to_string(),
-			font_size: FontSizeSpec::AutoFit,
-		},
+		shape: ShapeConfig::new(ShapeSource::Text("AI".to_string()), FontSizeSpec::AutoFit),
 		words: vec![
 			WordEntry::new("rust", 2.0),
 			WordEntry::new("svg", 1.0),
@@ -36,18 +71,25 @@ fn generate_with_same_seed_is_stable() {
 		],
 		style: StyleConfig {
 			font_size_range: 10..=20,
+			size_scaling: SizeScaling::Uniform,
 			padding: 0,
 			colors: vec!["#000".to_string()],
-			rotations: vec![Rotation::Deg0],
+			color_strategy: ColorStrategy::Random,
+			rotations: vec![Rotation::DEG0],
+			rotation_range: None,
+			collision: CollisionMode::BoundingBox,
 		},
 		algorithm: AlgorithmKind::FastGrid,
+		placement: PlacementMode::Weighted,
 		ratio_threshold: 0.3,
 		max_try_count: 1200,
 		seed: Some(99),
 		font,
+		fallback_fonts: Vec::new(),
 		render: RenderOptions {
 			show_progress: false,
 			debug_mask_out: None,
+			svg_text: SvgTextMode::Text,
 		},
 	};
 
@@ -55,3 +97,498 @@ fn generate_with_same_seed_is_stable() {
 	let b = generate(request).expect("generation should succeed");
 	assert_eq!(a.svg, b.svg);
 }
+
+#[test]
+fn glyph_collision_keeps_ink_inside_shape_without_overlap() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.style.collision = CollisionMode::Glyph;
+	request.style.padding = 1;
+
+	let result = generate(request.clone()).expect("generation should succeed");
+	assert!(!result.placements.is_empty());
+
+	let fonts = request.font_chain();
+	let (shape_mask, _) = resolve_shape_mask(&request.canvas, &request.shape, &fonts)
+		.expect("shape mask should build");
+	let mut inked = vec![false; request.canvas.width * request.canvas.height];
+
+	for (i, placement) in result.placements.iter().enumerate() {
+		let coverage = rasterize_text_coverage(
+			&placement.word,
+			&fonts,
+			placement.font_size,
+			request.style.padding,
+			placement.rotation,
+		);
+		for ((dy, dx), value) in coverage.indexed_iter() {
+			if !*value {
+				continue;
+			}
+			let (x, y) = (placement.x + dx, placement.y + dy);
+			assert!(
+				shape_mask[[y, x]],
+				"placement {i} leaves shape at ({x}, {y})"
+			);
+			let cell = &mut inked[y * request.canvas.width + x];
+			assert!(!*cell, "placement {i} overlaps another word at ({x}, {y})");
+			*cell = true;
+		}
+	}
+}
+
+#[test]
+fn ordered_placement_places_each_word_once_in_weight_order() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.placement = PlacementMode::Ordered { repeats: 1 };
+	request.ratio_threshold = 1.0;
+	request.words.push(WordEntry::new(
+		"an-unreasonably-long-word-that-cannot-fit",
+		0.5,
+	));
+
+	let result = generate(request.clone()).expect("generation should succeed");
+	let placed: Vec<&str> = result.placements.iter().map(|p| p.word.as_str()).collect();
+	let mut unique = placed.clone();
+	unique.sort_unstable();
+	unique.dedup();
+	assert_eq!(unique.len(), placed.len(), "words repeated: {placed:?}");
+	assert_eq!(placed.first(), Some(&"rust"));
+	assert!(
+		result
+			.unplaced_words
+			.contains(&"an-unreasonably-long-word-that-cannot-fit".to_string())
+	);
+	assert_eq!(
+		placed.len() + result.unplaced_words.len(),
+		request.words.len()
+	);
+
+	request.placement = PlacementMode::Ordered { repeats: 0 };
+	assert!(generate(request).is_err());
+}
+
+#[test]
+fn raster_render_scales_canvas_and_draws_words() {
+	let request = build_request(AlgorithmKind::FastGrid);
+	let result = generate(request.clone()).expect("generation should succeed");
+	let options = RasterOptions {
+		scale: 2.0,
+		background: Some("#ffffff".to_string()),
+	};
+
+	let image = render_raster(
+		&request.canvas,
+		&result.placements,
+		&request.font_chain(),
+		&options,
+	)
+	.expect("raster render should succeed");
+	assert_eq!(
+		image.dimensions(),
+		(
+			request.canvas.width as u32 * 2,
+			request.canvas.height as u32 * 2
+		)
+	);
+	assert_eq!(image.get_pixel(0, 0).0, [255, 255, 255, 255]);
+	assert!(image.pixels().any(|pixel| pixel.0 != [255, 255, 255, 255]));
+
+	let invalid = RasterOptions {
+		background: Some("not-a-color".to_string()),
+		..options
+	};
+	assert!(render_raster(&request.canvas, &[], &request.font_chain(), &invalid).is_err());
+}
+
+#[test]
+fn outline_mode_writes_paths_instead_of_text() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.render.svg_text = SvgTextMode::Outlines;
+
+	let result = generate(request).expect("generation should succeed");
+	assert!(!result.placements.is_empty());
+	assert!(!result.svg.contains("<text"));
+	assert!(!result.svg.contains("font-family"));
+	assert_eq!(result.svg.matches("<path").count(), result.placements.len());
+}
+
+#[test]
+fn embedded_font_mode_inlines_a_subset_that_keeps_placed_glyphs() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.render.svg_text = SvgTextMode::EmbeddedFont;
+
+	let result = generate(request.clone()).expect("generation should succeed");
+	assert!(result.svg.contains("@font-face"));
+	assert!(result.svg.contains("base64,"));
+	assert!(result.svg.contains("<text"));
+	let family = css_family_name(&font_family_name(&request.font));
+	assert!(
+		result
+			.svg
+			.contains(&format!("@font-face{{font-family:{family};"))
+	);
+	assert!(result.svg.contains(&format!(
+		"font-family=\"{}\"",
+		family.replace('"', "&quot;")
+	)));
+
+	let subset = subset_font(request.font.as_ref(), "rust").expect("subset should build");
+	assert!(subset.len() < request.font.data().len());
+	let face = FontFace::from_bytes(subset).expect("subset should parse");
+	assert!(face.rasterize('r', 32.0).0.width > 0);
+	assert_eq!(face.lookup_glyph_index('z'), 0);
+}
+
+#[test]
+fn subsets_keep_only_the_glyphs_a_text_needs() {
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/Roboto-Regular.ttf");
+	let source = load_font_from_file(&path).expect("font should load");
+	let text = "rusté";
+
+	let subset = subset_font(&source, text).expect("subset should build");
+	assert!(
+		subset.len() * 10 < source.data().len(),
+		"subset is {} of {} bytes",
+		subset.len(),
+		source.data().len()
+	);
+	let face = FontFace::from_bytes(subset).expect("subset should parse");
+	assert!(face.glyph_count() < source.glyph_count());
+	assert!(face.glyph_count() as usize <= 1 + text.chars().count() * 3);
+	for c in text.chars() {
+		assert_eq!(
+			face.rasterize(c, 32.0),
+			source.rasterize(c, 32.0),
+			"glyph {c}"
+		);
+	}
+	assert_eq!(face.lookup_glyph_index('z'), 0);
+}
+
+#[test]
+fn css_family_names_are_quoted() {
+	assert_eq!(css_family_name("Noto Sans SC"), "\"Noto Sans SC\"");
+	assert_eq!(css_family_name("3D \"Bold\""), r#""3D \"Bold\"""#);
+	assert_eq!(css_family_name(r"A\B"), r#""A\\B""#);
+}
+
+#[test]
+fn request_spec_and_result_round_trip_through_json() {
+	let font = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/NotoSansSC-Regular.ttf");
+	let job = serde_json::json!({
+		"canvas": { "width": 360, "height": 220, "margin": 8 },
+		"shape": { "source": { "text": "RUST" }, "font_size": "auto_fit" },
+		"words": [{ "text": "rust", "weight": 2.0 }, { "text": "svg", "weight": 1.0 }],
+		"style": { "font_size_range": { "start": 10, "end": 20 }, "rotations": [0, 450] },
+		"ratio_threshold": 0.2,
+		"max_try_count": 200,
+		"seed": 5,
+		"font": { "path": font },
+		"render": { "show_progress": false }
+	});
+
+	let spec: CloudRequestSpec = serde_json::from_value(job).expect("job should deserialize");
+	assert_eq!(spec.style.rotations, vec![Rotation::DEG0, Rotation::DEG90]);
+	let request = spec.clone().into_request().expect("font should load");
+	let result = generate(request).expect("generation should succeed");
+
+	let encoded = serde_json::to_string(&result).expect("result should serialize");
+	let decoded: CloudResult = serde_json::from_str(&encoded).expect("result should deserialize");
+	assert_eq!(decoded.svg, result.svg);
+	assert_eq!(decoded.placements.len(), result.placements.len());
+	assert_eq!(decoded.stats.seed, 5);
+
+	let respec: CloudRequestSpec =
+		serde_json::from_str(&serde_json::to_string(&spec).expect("spec should serialize"))
+			.expect("spec should deserialize");
+	assert_eq!(respec.words.len(), 2);
+}
+
+#[test]
+fn saved_layout_re_renders_with_new_colors_and_modes() {
+	let request = build_request(AlgorithmKind::FastGrid);
+	let result = generate(request.clone()).expect("generation should succeed");
+	let mut layout = CloudLayout::new(&request.canvas, "Test", &result);
+
+	let json = layout_to_json(&layout).expect("layout should encode");
+	let decoded = layout_from_json(&json).expect("layout should decode");
+	let svg = render_layout_svg(&decoded, &request.font_chain(), SvgTextMode::Text)
+		.expect("layout should render");
+	assert_eq!(svg, result.svg);
+
+	layout.recolor(&["#123456".to_string()]);
+	assert!(layout.placements.iter().all(|p| p.color == "#123456"));
+	let outlines = render_layout_svg(&layout, &request.font_chain(), SvgTextMode::Outlines)
+		.expect("layout should render as outlines");
+	assert_eq!(outlines.matches("<path").count(), layout.placements.len());
+
+	layout.placements[0].fixed_color = true;
+	layout.placements[0].color = "#ABCDEF".to_string();
+	let palette = ["#111111", "#222222", "#333333"].map(String::from);
+	layout.recolor(&palette);
+	assert_eq!(layout.placements[0].color, "#ABCDEF");
+	for placement in &layout.placements[1..] {
+		let first = layout.placements[1..]
+			.iter()
+			.find(|other| other.word == placement.word)
+			.expect("placement is in the list");
+		assert_eq!(placement.color, first.color);
+	}
+	assert_eq!(layout.placements[1].color, "#111111");
+
+	assert!(layout_from_json("{\"placements\": 3}").is_err());
+}
+
+#[test]
+fn saved_layout_records_the_fonts_it_was_measured_with() {
+	let request = build_request(AlgorithmKind::FastGrid);
+	let result = generate(request.clone()).expect("generation should succeed");
+	let fonts = request.font_chain();
+	let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/NotoSansSC-Regular.ttf");
+	let layout = CloudLayout::new(&request.canvas, font_family_name(fonts.primary()), &result);
+	assert!(layout.load_fonts().expect("no fonts to load").is_none());
+	assert!(layout.matches_font(&fonts));
+
+	let layout = layout.with_fonts(FontSource::Path(path), Vec::new());
+	let decoded = layout_from_json(&layout_to_json(&layout).expect("layout should encode"))
+		.expect("layout should decode");
+	let loaded = decoded
+		.load_fonts()
+		.expect("recorded font should load")
+		.expect("layout records a font");
+	assert!(decoded.matches_font(&loaded));
+	let svg =
+		render_layout_svg(&decoded, &loaded, SvgTextMode::Text).expect("layout should render");
+	assert_eq!(svg, result.svg);
+
+	let other = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/Roboto-Regular.ttf");
+	let other = FontChain::from(load_font_from_file(other).expect("font should load"));
+	assert!(!decoded.matches_font(&other));
+}
+
+#[test]
+fn per_word_overrides_apply_to_every_placement() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.placement = PlacementMode::Ordered { repeats: 1 };
+	request.style.rotations = vec![Rotation::DEG0, Rotation::DEG90];
+	request.words[0] = WordEntry::new("rust", 3.0)
+		.with_color("#123456")
+		.with_rotations(vec![Rotation::DEG0])
+		.with_font_size(14)
+		.with_link("https://www.rust-lang.org")
+		.with_class("brand");
+
+	let result = generate(request).expect("generation should succeed");
+	let rust: Vec<_> = result
+		.placements
+		.iter()
+		.filter(|p| p.word == "rust")
+		.collect();
+	assert!(!rust.is_empty());
+	assert!(rust.iter().all(|p| {
+		p.color == "#123456" && p.fixed_color && p.rotation == Rotation::DEG0 && p.font_size <= 14
+	}));
+	assert!(result.svg.contains("<a href=\"https://www.rust-lang.org\""));
+	assert!(result.svg.contains("class=\"brand\""));
+}
+
+#[test]
+fn multi_line_and_layered_shapes_fill_their_regions() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	let fonts = request.font_chain();
+	let mask_of = |shape: &ShapeConfig| {
+		resolve_shape_mask(&request.canvas, shape, &fonts).expect("shape mask should build")
+	};
+
+	let (_, single_size) = mask_of(&ShapeConfig::new(
+		ShapeSource::Text("DATAWEEK".to_string()),
+		FontSizeSpec::AutoFit,
+	));
+	let (_, stacked_size) = mask_of(&ShapeConfig::new(
+		ShapeSource::Text("DATA\nWEEK".to_string()),
+		FontSizeSpec::AutoFit,
+	));
+	assert!(stacked_size > single_size);
+
+	let layer = |text: &str, y: usize, op: ShapeOp| ShapeLayer {
+		shape: ShapeConfig::new(ShapeSource::Text(text.to_string()), FontSizeSpec::AutoFit),
+		region: Some(ShapeRegion {
+			x: 0,
+			y,
+			width: 360,
+			height: 110,
+		}),
+		op,
+	};
+	request.shape = ShapeConfig::new(
+		ShapeSource::Layers(vec![
+			layer("DATA", 0, ShapeOp::Union),
+			layer("WEEK", 110, ShapeOp::Union),
+			layer("EE", 110, ShapeOp::Subtract),
+		]),
+		FontSizeSpec::AutoFit,
+	);
+	let (layered, _) = mask_of(&request.shape);
+	let top = layered
+		.slice(ndarray::s![..110, ..])
+		.iter()
+		.filter(|v| **v)
+		.count();
+	let bottom = layered
+		.slice(ndarray::s![110.., ..])
+		.iter()
+		.filter(|v| **v)
+		.count();
+	assert!(top > 0 && bottom > 0);
+
+	let result = generate(request).expect("layered shape should generate");
+	assert!(!result.placements.is_empty());
+}
+
+#[test]
+fn fallback_fonts_draw_characters_the_primary_lacks() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	let latin = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/Roboto-Regular.ttf");
+	request.fallback_fonts = vec![Arc::clone(&request.font)];
+	request.font = Arc::new(load_font_from_file(latin).expect("latin font should load"));
+	request.placement = PlacementMode::Ordered { repeats: 1 };
+	request.words.insert(0, WordEntry::new("云rust", 4.0));
+
+	let fonts = request.font_chain();
+	assert_eq!(fonts.resolve('r'), 0);
+	assert_eq!(fonts.resolve('云'), 1);
+	let line = LineLayout::new("云rust", &fonts, 32.0);
+	assert_eq!(
+		line.glyphs[0].metrics,
+		request.fallback_fonts[0].metrics('云', 32.0)
+	);
+	assert_eq!(line.glyphs[1].metrics, request.font.metrics('r', 32.0));
+
+	let result = generate(request.clone()).expect("generation should succeed");
+	assert!(result.placements.iter().any(|p| p.word == "云rust"));
+	let family_attr = |text: &str| {
+		format!(
+			"font-family=\"{}\"",
+			fonts.family_list_for(text).replace('"', "&quot;")
+		)
+	};
+	assert_eq!(fonts.family_list_for("云rust"), fonts.family_list());
+	assert_eq!(
+		fonts.family_list_for("rust"),
+		css_family_name(&font_family_name(&request.font))
+	);
+	assert!(result.svg.contains(&family_attr("云rust")));
+	assert!(result.svg.contains(&family_attr("rust")));
+
+	request.render.svg_text = SvgTextMode::EmbeddedFont;
+	let embedded = generate(request).expect("generation should succeed");
+	assert_eq!(embedded.svg.matches("@font-face").count(), 2);
+}
+
+#[test]
+fn shape_font_draws_the_mask_while_words_keep_their_font() {
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	let display = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/Roboto-Regular.ttf");
+	let display_family =
+		font_family_name(&load_font_from_file(&display).expect("font should load"));
+	let fonts = request.font_chain();
+	let (word_mask, _) = resolve_shape_mask(&request.canvas, &request.shape, &fonts)
+		.expect("shape mask should build");
+
+	request.shape = request.shape.with_font(FontSource::Path(display.clone()));
+	let (shape_mask, _) = resolve_shape_mask(&request.canvas, &request.shape, &fonts)
+		.expect("shape mask should build");
+	assert_ne!(shape_mask, word_mask);
+
+	let result = generate(request.clone()).expect("generation should succeed");
+	assert!(result.svg.contains(&format!(
+		"font-family=\"{}\"",
+		fonts.family_list().replace('"', "&quot;")
+	)));
+	assert!(!result.svg.contains(&display_family));
+
+	request.shape.font = Some(FontSource::Path("".into()));
+	assert!(generate(request.clone()).is_err());
+
+	// Only shapes that draw text load their font; layers inherit it.
+	let missing = FontSource::Path("missing-font.ttf".into());
+	let square = ShapeConfig::new(
+		ShapeSource::Svg(SvgMaskSource::Path {
+			data: "M0 0 H10 V10 H0 Z".to_string(),
+			fill_rule: Default::default(),
+		}),
+		FontSizeSpec::AutoFit,
+	);
+	let text = ShapeConfig::new(ShapeSource::Text("RUST".to_string()), FontSizeSpec::AutoFit);
+	let layered = |layer: ShapeConfig| {
+		ShapeConfig::new(
+			ShapeSource::Layers(vec![ShapeLayer {
+				shape: layer,
+				region: None,
+				op: ShapeOp::Union,
+			}]),
+			FontSizeSpec::AutoFit,
+		)
+		.with_font(missing.clone())
+	};
+	let resolves = |shape: &ShapeConfig| resolve_shape_mask(&request.canvas, shape, &fonts).is_ok();
+	assert!(resolves(&square.clone().with_font(missing.clone())));
+	assert!(resolves(&layered(square)));
+	assert!(resolves(&layered(
+		text.clone().with_font(FontSource::Path(display))
+	)));
+	assert!(!resolves(&layered(text)));
+}
+
+#[test]
+fn collection_faces_load_by_index() {
+	let fonts_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts");
+	let faces = ["Roboto-Regular.ttf", "NotoSansSC-Regular.ttf"]
+		.map(|name| std::fs::read(fonts_dir.join(name)).expect("test font should read"));
+
+	// A `ttcf` header followed by both fonts, their table offsets made
+	// relative to the start of the collection.
+	let mut collection = b"ttcf\0\x01\0\0".to_vec();
+	collection.extend((faces.len() as u32).to_be_bytes());
+	let mut start = 12 + 4 * faces.len();
+	let mut body = Vec::new();
+	for face in &faces {
+		collection.extend((start as u32).to_be_bytes());
+		let mut face = face.clone();
+		let num_tables = u16::from_be_bytes([face[4], face[5]]) as usize;
+		for record in 0..num_tables {
+			let field = 12 + record * 16 + 8;
+			let offset = u32::from_be_bytes(face[field..field + 4].try_into().unwrap());
+			face[field..field + 4].copy_from_slice(&(offset + start as u32).to_be_bytes());
+		}
+		start += face.len();
+		body.extend(face);
+	}
+	collection.extend(body);
+
+	let dir = tempfile::tempdir().expect("tempdir should be created");
+	let path = dir.path().join("pair.ttc");
+	std::fs::write(&path, collection).expect("collection should be written");
+	let load = |index: u32| load_font_from_file(format!("{}#{index}", path.display()));
+
+	for (index, face) in faces.into_iter().enumerate() {
+		let expected = FontFace::from_bytes(face).expect("test font should parse");
+		let loaded = load(index as u32).expect("collection face should load");
+		assert_eq!(loaded.index(), index as u32);
+		assert_eq!(font_family_name(&loaded), font_family_name(&expected));
+		assert_eq!(loaded.metrics('r', 24.0), expected.metrics('r', 24.0));
+	}
+	assert_eq!(
+		font_family_name(&load_font_from_file(&path).expect("face 0 is the default")),
+		font_family_name(&load(0).expect("collection face should load"))
+	);
+
+	let err = load(2).expect_err("collection has two faces");
+	assert!(err.to_string().contains("out of range"));
+
+	let mut request = build_request(AlgorithmKind::FastGrid);
+	request.font = Arc::new(load(1).expect("collection face should load"));
+	request.render.svg_text = SvgTextMode::EmbeddedFont;
+	let result = generate(request).expect("generation should succeed");
+	assert!(result.svg.contains("@font-face"));
+}
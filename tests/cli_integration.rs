@@ -1,4 +1,6 @@
-use std::process::Command;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
 use tempfile::tempdir;
 
 fn test_font_path() -> std::path::PathBuf {
@@ -35,6 +37,305 @@ fn cli_generates_svg_file() {
 	assert!(content.contains("<svg"));
 }
 
+#[test]
+fn cli_generates_png_from_output_extension() {
+	let dir = tempdir().expect("tempdir should be created");
+	let output = dir.path().join("cloud.png");
+	let font = test_font_path();
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args([
+			"--text",
+			"RUST",
+			"--words",
+			"rust,cloud,layout",
+			"--canvas-size",
+			"300,160",
+			"--seed",
+			"7",
+			"--scale",
+			"1.5",
+			"--background",
+			"white",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--output"])
+		.arg(&output)
+		.status()
+		.expect("process should run");
+
+	assert!(status.success());
+
+	let image = image::open(&output).expect("png should be written");
+	assert_eq!((image.width(), image.height()), (450, 240));
+}
+
+#[test]
+fn cli_writes_layout_json_next_to_svg() {
+	let dir = tempdir().expect("tempdir should be created");
+	let output = dir.path().join("cloud.svg");
+	let layout = dir.path().join("layout.json");
+	let font = test_font_path();
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args([
+			"--text",
+			"RUST",
+			"--words",
+			"rust,cloud,layout",
+			"--canvas-size",
+			"360,220",
+			"--seed",
+			"11",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--layout-out"])
+		.arg(&layout)
+		.arg("--output")
+		.arg(&output)
+		.status()
+		.expect("process should run");
+
+	assert!(status.success());
+
+	let content = std::fs::read_to_string(&layout).expect("layout should be written");
+	let json: serde_json::Value = serde_json::from_str(&content).expect("layout should be JSON");
+	let placements = json["placements"].as_array().expect("placements array");
+	assert!(!placements.is_empty());
+	for key in [
+		"x",
+		"y",
+		"width",
+		"height",
+		"font_size",
+		"color",
+		"rotation",
+	] {
+		assert!(
+			placements[0].get(key).is_some(),
+			"placement is missing {key}"
+		);
+	}
+	assert_eq!(json["stats"]["seed"], 11);
+	assert_eq!(json["canvas"]["width"], 360);
+}
+
+#[test]
+fn cli_render_subcommand_redraws_saved_layout() {
+	let dir = tempdir().expect("tempdir should be created");
+	let output = dir.path().join("cloud.svg");
+	let layout = dir.path().join("layout.json");
+	let font = test_font_path();
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args([
+			"--text",
+			"RUST",
+			"--words",
+			"rust,cloud,layout",
+			"--canvas-size",
+			"360,220",
+			"--seed",
+			"11",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--layout-out"])
+		.arg(&layout)
+		.arg("--output")
+		.arg(&output)
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+	let json: serde_json::Value =
+		serde_json::from_str(&std::fs::read_to_string(&layout).expect("layout should be written"))
+			.expect("layout should be JSON");
+	let recorded = json["font"]["path"]
+		.as_str()
+		.expect("layout records its font");
+	assert!(Path::new(recorded).is_absolute());
+	assert!(recorded.ends_with(font.file_name().unwrap().to_str().unwrap()));
+
+	let rerendered = dir.path().join("recolored.svg");
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.arg("render")
+		.arg(&layout)
+		.args(["--colors", "#ff0000", "--font"])
+		.arg(&font)
+		.arg("--output")
+		.arg(&rerendered)
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+
+	let original = std::fs::read_to_string(&output).expect("svg should be written");
+	let recolored = std::fs::read_to_string(&rerendered).expect("svg should be written");
+	assert_eq!(
+		original.matches("<text").count(),
+		recolored.matches("<text").count()
+	);
+	assert!(recolored.contains("fill=\"#ff0000\""));
+
+	let png = dir.path().join("cloud.png");
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.arg("render")
+		.arg(&layout)
+		.args(["--background", "white", "--output"])
+		.arg(&png)
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+	let image = image::open(&png).expect("png should decode");
+	assert_eq!((image.width(), image.height()), (360, 220));
+}
+
+#[test]
+fn cli_reads_tsv_words_from_stdin_with_named_columns() {
+	let dir = tempdir().expect("tempdir should be created");
+	let output = dir.path().join("cloud.svg");
+	let font = test_font_path();
+
+	let mut child = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args([
+			"--text",
+			"RUST",
+			"--word-file",
+			"-",
+			"--word-column",
+			"term",
+			"--weight-column",
+			"count",
+			"--placement",
+			"ordered",
+			"--canvas-size",
+			"360,220",
+			"--seed",
+			"5",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--output"])
+		.arg(&output)
+		.stdin(Stdio::piped())
+		.spawn()
+		.expect("process should run");
+	child
+		.stdin
+		.take()
+		.expect("stdin should be piped")
+		.write_all(b"id\tterm\tcount\n1\trust\t5\n2\t\"hello, world\"\t2\n")
+		.expect("stdin should accept words");
+	let status = child.wait().expect("process should finish");
+
+	assert!(status.success());
+	let svg = std::fs::read_to_string(&output).expect("svg should be written");
+	assert!(svg.contains("rust"));
+	assert!(svg.contains("hello, world"));
+}
+
+#[test]
+fn cli_reads_headerless_csv_with_numbered_columns() {
+	let dir = tempdir().expect("tempdir should be created");
+	let words = dir.path().join("words.csv");
+	let layout = dir.path().join("layout.json");
+	std::fs::write(&words, "rust,5\nsvg,3\ncloud,2\n").expect("words should be written");
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args(["--text", "RUST", "--word-file"])
+		.arg(&words)
+		.args([
+			"--word-column",
+			"1",
+			"--weight-column",
+			"2",
+			"--canvas-size",
+			"360,220",
+			"--seed",
+			"5",
+			"--font",
+		])
+		.arg(test_font_path())
+		.args(["--no-progress", "--layout-out"])
+		.arg(&layout)
+		.arg("--output")
+		.arg(dir.path().join("cloud.svg"))
+		.status()
+		.expect("process should run");
+
+	assert!(status.success());
+	let content = std::fs::read_to_string(&layout).expect("layout should be written");
+	let json: serde_json::Value = serde_json::from_str(&content).expect("layout should be JSON");
+	let mut loaded: Vec<&str> = json["placements"]
+		.as_array()
+		.expect("placements array")
+		.iter()
+		.filter_map(|placement| placement["word"].as_str())
+		.chain(
+			json["unplaced_words"]
+				.as_array()
+				.expect("unplaced words array")
+				.iter()
+				.filter_map(|word| word.as_str()),
+		)
+		.collect();
+	loaded.sort_unstable();
+	loaded.dedup();
+	assert_eq!(loaded, vec!["cloud", "rust", "svg"]);
+}
+
+#[test]
+fn cli_counts_words_from_text_corpus() {
+	let dir = tempdir().expect("tempdir should be created");
+	let output = dir.path().join("cloud.svg");
+	let corpus = dir.path().join("corpus.txt");
+	let font = test_font_path();
+	std::fs::write(
+		&corpus,
+		"The glyph and the weave. Glyph, glyph, weave and the loom.",
+	)
+	.expect("corpus should be written");
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args(["--text", "RUST", "--text-file"])
+		.arg(&corpus)
+		.args([
+			"--stop-words",
+			"loom",
+			"--max-words",
+			"2",
+			"--placement",
+			"ordered",
+			"--canvas-size",
+			"360,220",
+			"--seed",
+			"9",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--layout-out"])
+		.arg(dir.path().join("layout.json"))
+		.arg("--output")
+		.arg(&output)
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+
+	let layout =
+		std::fs::read_to_string(dir.path().join("layout.json")).expect("layout should be written");
+	let json: serde_json::Value = serde_json::from_str(&layout).expect("layout should be JSON");
+	let mut words: Vec<&str> = json["placements"]
+		.as_array()
+		.expect("placements array")
+		.iter()
+		.filter_map(|placement| placement["word"].as_str())
+		.collect();
+	words.sort_unstable();
+	words.dedup();
+	assert_eq!(words, vec!["glyph", "weave"]);
+}
+
 #[test]
 fn cli_returns_invalid_config_exit_code_when_words_missing() {
 	let dir = tempdir().expect("tempdir should be created");
@@ -55,38 +356,236 @@ fn cli_returns_invalid_config_exit_code_when_words_missing() {
 }
 
 #[test]
-fn cli_can_write_debug_mask() {
+fn cli_mask_subcommand_writes_mask_and_area_stats() {
 	let dir = tempdir().expect("tempdir should be created");
-	let output = dir.path().join("cloud.svg");
 	let mask = dir.path().join("mask.png");
 	let font = test_font_path();
 
-	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+	let result = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
 		.args([
+			"mask",
 			"--text",
 			"RUST",
+			"--canvas-size",
+			"420,240",
+			"--font",
+		])
+		.arg(&font)
+		.arg("--output")
+		.arg(&mask)
+		.output()
+		.expect("process should run");
+
+	assert!(result.status.success());
+	assert!(mask.exists());
+	let stdout = String::from_utf8_lossy(&result.stdout);
+	assert!(stdout.contains("canvas: 420x240"));
+	assert!(stdout.contains("usable area:"));
+}
+
+#[test]
+fn cli_generate_subcommand_matches_flat_invocation() {
+	let dir = tempdir().expect("tempdir should be created");
+	let flat = dir.path().join("flat.svg");
+	let sub = dir.path().join("sub.svg");
+	let font = test_font_path();
+	let common = [
+		"--text",
+		"RUST",
+		"--words",
+		"rust,cloud",
+		"--canvas-size",
+		"360,220",
+		"--seed",
+		"3",
+		"--no-progress",
+		"--font",
+	];
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args(common)
+		.arg(&font)
+		.arg("--output")
+		.arg(&flat)
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.arg("generate")
+		.args(common)
+		.arg(&font)
+		.arg("--output")
+		.arg(&sub)
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+
+	assert_eq!(
+		std::fs::read_to_string(&flat).expect("svg should be written"),
+		std::fs::read_to_string(&sub).expect("svg should be written")
+	);
+}
+
+#[test]
+fn cli_palette_subcommand_prints_colors() {
+	let result = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args(["palette", "triadic", "--palette-size", "3"])
+		.output()
+		.expect("process should run");
+
+	assert!(result.status.success());
+	let stdout = String::from_utf8_lossy(&result.stdout);
+	let colors: Vec<&str> = stdout.lines().collect();
+	assert_eq!(colors.len(), 3);
+	assert!(colors.iter().all(|color| color.starts_with('#')));
+}
+
+#[test]
+fn cli_generates_svg_from_mask_image() {
+	let dir = tempdir().expect("tempdir should be created");
+	let output = dir.path().join("cloud.svg");
+	let mask = dir.path().join("logo.png");
+	let font = test_font_path();
+
+	let logo = image::RgbaImage::from_fn(120, 80, |x, y| {
+		let dx = x as f32 - 60.0;
+		let dy = y as f32 - 40.0;
+		if dx * dx + dy * dy < 36.0 * 36.0 {
+			image::Rgba([0, 0, 0, 255])
+		} else {
+			image::Rgba([0, 0, 0, 0])
+		}
+	});
+	logo.save(&mask).expect("mask image should be written");
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.arg("--mask-image")
+		.arg(&mask)
+		.args([
 			"--words",
 			"rust,cloud,layout",
 			"--canvas-size",
-			"420,240",
-			"--algorithm",
-			"random-baseline",
+			"420,280",
 			"--seed",
-			"123",
+			"7",
 			"--ratio",
-			"0.2",
+			"0.3",
 			"--max-tries",
-			"200",
+			"400",
 			"--font",
 		])
 		.arg(&font)
-		.args(["--debug-mask-out"])
-		.arg(&mask)
 		.args(["--no-progress", "--output"])
 		.arg(&output)
 		.status()
 		.expect("process should run");
 
 	assert!(status.success());
-	assert!(mask.exists());
+	let content = std::fs::read_to_string(&output).expect("svg should be written");
+	assert!(content.contains("<text"));
+}
+
+#[test]
+fn cli_colors_words_by_group_tag() {
+	let dir = tempdir().expect("tempdir should be created");
+	let words = dir.path().join("words.txt");
+	let layout = dir.path().join("layout.json");
+	let font = test_font_path();
+	std::fs::write(
+		&words,
+		"rust,3,group=lang\ngo,2,group=lang\ncloud,2,group=infra\nmask,1,group=infra\n",
+	)
+	.expect("word file should be written");
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.args(["--text", "RUST", "--word-file"])
+		.arg(&words)
+		.args([
+			"--color-by",
+			"group",
+			"--colors",
+			"#1D4ED8,#DB2777",
+			"--canvas-size",
+			"360,220",
+			"--seed",
+			"5",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--layout-out"])
+		.arg(&layout)
+		.arg("--output")
+		.arg(dir.path().join("cloud.svg"))
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+
+	let layout = std::fs::read_to_string(&layout).expect("layout should be written");
+	let json: serde_json::Value = serde_json::from_str(&layout).expect("layout should be JSON");
+	let placements = json["placements"].as_array().expect("placements array");
+	assert!(!placements.is_empty());
+	for placement in placements {
+		let expected = match placement["word"].as_str() {
+			Some("cloud" | "mask") => "#1D4ED8",
+			_ => "#DB2777",
+		};
+		assert_eq!(placement["color"], expected);
+	}
+}
+
+#[test]
+fn cli_samples_word_colors_from_mask_image() {
+	let dir = tempdir().expect("tempdir should be created");
+	let mask = dir.path().join("photo.png");
+	let layout = dir.path().join("layout.json");
+	let font = test_font_path();
+
+	let photo = image::RgbaImage::from_fn(120, 80, |x, _| {
+		if x < 60 {
+			image::Rgba([220, 0, 0, 255])
+		} else {
+			image::Rgba([0, 0, 220, 255])
+		}
+	});
+	photo.save(&mask).expect("photo should be written");
+
+	let status = Command::new(env!("CARGO_BIN_EXE_glyphweave"))
+		.arg("--mask-image")
+		.arg(&mask)
+		.args([
+			"--mask-channel",
+			"alpha",
+			"--words",
+			"rust,cloud,layout",
+			"--color-by",
+			"image",
+			"--canvas-size",
+			"420,280",
+			"--seed",
+			"7",
+			"--ratio",
+			"0.3",
+			"--max-tries",
+			"400",
+			"--font",
+		])
+		.arg(&font)
+		.args(["--no-progress", "--layout-out"])
+		.arg(&layout)
+		.arg("--output")
+		.arg(dir.path().join("cloud.svg"))
+		.status()
+		.expect("process should run");
+	assert!(status.success());
+
+	let layout = std::fs::read_to_string(&layout).expect("layout should be written");
+	let json: serde_json::Value = serde_json::from_str(&layout).expect("layout should be JSON");
+	let placements = json["placements"].as_array().expect("placements array");
+	assert!(!placements.is_empty());
+	for placement in placements {
+		let color = placement["color"].as_str().expect("color string");
+		assert_eq!(color.len(), 7, "unexpected color {color}");
+		assert_eq!(&color[3..5], "00", "green should stay empty in {color}");
+	}
 }
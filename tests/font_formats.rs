@@ -0,0 +1,372 @@
+use glyphweave::font::FontFace;
+use glyphweave::load_font_from_file;
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::path::Path;
+
+fn source_font() -> std::path::PathBuf {
+	Path::new(env!("CARGO_MANIFEST_DIR")).join("fonts/Roboto-Regular.ttf")
+}
+
+fn u16_at(data: &[u8], offset: usize) -> u16 {
+	u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+fn u32_at(data: &[u8], offset: usize) -> u32 {
+	u32::from_be_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+// Tables of an sfnt in directory (tag) order.
+fn sfnt_tables(data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+	(0..u16_at(data, 4) as usize)
+		.map(|index| {
+			let record = 12 + index * 16;
+			let offset = u32_at(data, record + 8) as usize;
+			let length = u32_at(data, record + 12) as usize;
+			(
+				data[record..record + 4].try_into().unwrap(),
+				&data[offset..offset + length],
+			)
+		})
+		.collect()
+}
+
+fn encode_woff1(sfnt: &[u8]) -> Vec<u8> {
+	let tables = sfnt_tables(sfnt);
+	let mut directory = Vec::new();
+	let mut payload = Vec::new();
+	let data_start = 44 + tables.len() * 20;
+	for (tag, table) in &tables {
+		let mut encoder =
+			flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+		encoder.write_all(table).unwrap();
+		let compressed = encoder.finish().unwrap();
+		let stored = if compressed.len() < table.len() {
+			compressed
+		} else {
+			table.to_vec()
+		};
+		directory.extend(tag);
+		directory.extend(((data_start + payload.len()) as u32).to_be_bytes());
+		directory.extend((stored.len() as u32).to_be_bytes());
+		directory.extend((table.len() as u32).to_be_bytes());
+		directory.extend([0u8; 4]);
+		payload.extend(stored);
+		payload.resize(payload.len().next_multiple_of(4), 0);
+	}
+
+	let mut woff = Vec::new();
+	woff.extend(b"wOFF");
+	woff.extend(&sfnt[0..4]);
+	woff.extend(((data_start + payload.len()) as u32).to_be_bytes());
+	woff.extend((tables.len() as u16).to_be_bytes());
+	woff.resize(44, 0);
+	woff.extend(directory);
+	woff.extend(payload);
+	woff
+}
+
+fn base128(out: &mut Vec<u8>, value: usize) {
+	let groups = (1..5).take_while(|shift| value >> (7 * shift) != 0).count();
+	for shift in (0..=groups).rev() {
+		let continuation = if shift > 0 { 0x80 } else { 0 };
+		out.push(continuation | ((value >> (7 * shift)) & 0x7f) as u8);
+	}
+}
+
+fn u255(out: &mut Vec<u8>, value: u16) {
+	if value < 253 {
+		out.push(value as u8);
+	} else {
+		out.push(253);
+		out.extend(value.to_be_bytes());
+	}
+}
+
+// Writes `data` as a Brotli stream of stored (uncompressed) meta-blocks, which
+// every decoder accepts and needs no encoder.
+fn brotli_stored(data: &[u8]) -> Vec<u8> {
+	let mut bits = BitWriter::default();
+	// WBITS = 16.
+	bits.push(0, 1);
+	for chunk in data.chunks(1 << 16) {
+		// ISLAST = 0, MNIBBLES = 4, MLEN - 1, ISUNCOMPRESSED = 1.
+		bits.push(0, 1);
+		bits.push(0, 2);
+		bits.push(chunk.len() as u32 - 1, 16);
+		bits.push(1, 1);
+		bits.align();
+		bits.bytes.extend(chunk);
+	}
+	// ISLAST = 1, ISLASTEMPTY = 1.
+	bits.push(0b11, 2);
+	bits.align();
+	bits.bytes
+}
+
+#[derive(Default)]
+struct BitWriter {
+	bytes: Vec<u8>,
+	used: u32,
+}
+
+impl BitWriter {
+	fn push(&mut self, value: u32, count: u32) {
+		for bit in 0..count {
+			if self.used == 0 {
+				self.bytes.push(0);
+			}
+			*self.bytes.last_mut().unwrap() |= (((value >> bit) & 1) as u8) << self.used;
+			self.used = (self.used + 1) % 8;
+		}
+	}
+
+	fn align(&mut self) {
+		self.used = 0;
+	}
+}
+
+// The WOFF2 glyf transform: contour counts, point counts, flags, coordinates,
+// composites, bounding boxes and instructions each go to their own stream.
+fn transform_glyf(glyf: &[u8], loca: &[u8], long_loca: bool, num_glyphs: usize) -> Vec<u8> {
+	let offset = |index: usize| {
+		if long_loca {
+			u32_at(loca, index * 4) as usize
+		} else {
+			u16_at(loca, index * 2) as usize * 2
+		}
+	};
+	let mut streams: [Vec<u8>; 7] = Default::default();
+	let [
+		contours,
+		points,
+		flags,
+		glyphs,
+		composites,
+		bboxes,
+		instructions,
+	] = &mut streams;
+	let mut bbox_bitmap = vec![0u8; num_glyphs.div_ceil(32) * 4];
+
+	for glyph in 0..num_glyphs {
+		let data = &glyf[offset(glyph)..offset(glyph + 1)];
+		if data.is_empty() {
+			contours.extend(0i16.to_be_bytes());
+			continue;
+		}
+		let num_contours = u16_at(data, 0) as i16;
+		contours.extend(num_contours.to_be_bytes());
+		bbox_bitmap[glyph / 8] |= 0x80 >> (glyph % 8);
+		bboxes.extend(&data[2..10]);
+
+		if num_contours < 0 {
+			let mut pos = 10;
+			let mut has_instructions = false;
+			loop {
+				let component_flags = u16_at(data, pos);
+				let mut length = 4 + if component_flags & 0x0001 != 0 { 4 } else { 2 };
+				length += match component_flags {
+					f if f & 0x0008 != 0 => 2,
+					f if f & 0x0040 != 0 => 4,
+					f if f & 0x0080 != 0 => 8,
+					_ => 0,
+				};
+				composites.extend(&data[pos..pos + length]);
+				pos += length;
+				has_instructions |= component_flags & 0x0100 != 0;
+				if component_flags & 0x0020 == 0 {
+					break;
+				}
+			}
+			if has_instructions {
+				let length = u16_at(data, pos);
+				u255(glyphs, length);
+				instructions.extend(&data[pos + 2..pos + 2 + length as usize]);
+			}
+			continue;
+		}
+
+		let mut previous_end = -1i32;
+		for contour in 0..num_contours as usize {
+			let end = u16_at(data, 10 + contour * 2) as i32;
+			u255(points, (end - previous_end) as u16);
+			previous_end = end;
+		}
+		let num_points = (previous_end + 1) as usize;
+		let mut pos = 10 + num_contours as usize * 2;
+		let instruction_length = u16_at(data, pos) as usize;
+		let instruction_bytes = &data[pos + 2..pos + 2 + instruction_length];
+		pos += 2 + instruction_length;
+
+		let mut point_flags = Vec::with_capacity(num_points);
+		while point_flags.len() < num_points {
+			let flag = data[pos];
+			pos += 1;
+			point_flags.push(flag);
+			if flag & 0x08 != 0 {
+				point_flags.extend(std::iter::repeat_n(flag, data[pos] as usize));
+				pos += 1;
+			}
+		}
+		let mut deltas = vec![(0i32, 0i32); num_points];
+		for (axis, short, same) in [(0, 0x02, 0x10), (1, 0x04, 0x20)] {
+			for (flag, delta) in point_flags.iter().zip(&mut deltas) {
+				let value = if flag & short != 0 {
+					pos += 1;
+					let magnitude = data[pos - 1] as i32;
+					if flag & same != 0 {
+						magnitude
+					} else {
+						-magnitude
+					}
+				} else if flag & same != 0 {
+					0
+				} else {
+					pos += 2;
+					u16_at(data, pos - 2) as i16 as i32
+				};
+				if axis == 0 {
+					delta.0 = value;
+				} else {
+					delta.1 = value;
+				}
+			}
+		}
+		// Every point uses the five-byte triplet form: 16 bits per axis.
+		for (flag, (dx, dy)) in point_flags.iter().zip(deltas) {
+			let off_curve = if flag & 0x01 == 0 { 0x80 } else { 0 };
+			let signs = u8::from(dx >= 0) | u8::from(dy >= 0) << 1;
+			flags.push(off_curve | (124 + signs));
+			glyphs.extend((dx.unsigned_abs() as u16).to_be_bytes());
+			glyphs.extend((dy.unsigned_abs() as u16).to_be_bytes());
+		}
+		u255(glyphs, instruction_length as u16);
+		instructions.extend(instruction_bytes);
+	}
+
+	bbox_bitmap.extend(std::mem::take(bboxes));
+	*bboxes = bbox_bitmap;
+	let mut table = Vec::new();
+	table.extend([0u8; 4]);
+	table.extend((num_glyphs as u16).to_be_bytes());
+	table.extend(u16::from(long_loca).to_be_bytes());
+	for stream in &streams {
+		table.extend((stream.len() as u32).to_be_bytes());
+	}
+	for stream in streams {
+		table.extend(stream);
+	}
+	table
+}
+
+fn encode_woff2(sfnt: &[u8]) -> Vec<u8> {
+	let tables = sfnt_tables(sfnt);
+	let table = |tag: &[u8; 4]| tables.iter().find(|(t, _)| t == tag).unwrap().1;
+	let long_loca = u16_at(table(b"head"), 50) == 1;
+	let num_glyphs = u16_at(table(b"maxp"), 4) as usize;
+
+	let mut directory = Vec::new();
+	let mut stream = Vec::new();
+	for (tag, data) in &tables {
+		match tag {
+			b"glyf" => {
+				let transformed = transform_glyf(data, table(b"loca"), long_loca, num_glyphs);
+				directory.push(10);
+				base128(&mut directory, data.len());
+				base128(&mut directory, transformed.len());
+				stream.extend(transformed);
+			}
+			b"loca" => {
+				directory.push(11);
+				base128(&mut directory, data.len());
+				base128(&mut directory, 0);
+			}
+			_ => {
+				directory.push(63);
+				directory.extend(tag);
+				base128(&mut directory, data.len());
+				stream.extend(*data);
+			}
+		}
+	}
+	let compressed = brotli_stored(&stream);
+
+	let mut woff2 = Vec::new();
+	woff2.extend(b"wOF2");
+	woff2.extend(&sfnt[0..4]);
+	woff2.extend(((48 + directory.len() + compressed.len()) as u32).to_be_bytes());
+	woff2.extend((tables.len() as u16).to_be_bytes());
+	woff2.extend([0u8; 2]);
+	woff2.extend((sfnt.len() as u32).to_be_bytes());
+	woff2.extend((compressed.len() as u32).to_be_bytes());
+	woff2.resize(48, 0);
+	woff2.extend(directory);
+	woff2.extend(compressed);
+	woff2
+}
+
+#[derive(Default)]
+struct OutlineRecorder(String);
+
+impl ttf_parser::OutlineBuilder for OutlineRecorder {
+	fn move_to(&mut self, x: f32, y: f32) {
+		write!(self.0, "M{x},{y}").unwrap();
+	}
+
+	fn line_to(&mut self, x: f32, y: f32) {
+		write!(self.0, "L{x},{y}").unwrap();
+	}
+
+	fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+		write!(self.0, "Q{x1},{y1},{x},{y}").unwrap();
+	}
+
+	fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+		write!(self.0, "C{x1},{y1},{x2},{y2},{x},{y}").unwrap();
+	}
+
+	fn close(&mut self) {
+		self.0.push('Z');
+	}
+}
+
+fn assert_same_font(decoded: &FontFace, source: &FontFace) {
+	assert_eq!(decoded.glyph_count(), source.glyph_count());
+	assert_eq!(
+		decoded.metrics('R', 48.0).advance_width,
+		source.metrics('R', 48.0).advance_width
+	);
+
+	let decoded = decoded.parse_outlines().expect("decoded font should parse");
+	let source = source.parse_outlines().expect("source font should parse");
+	for glyph in 0..source.number_of_glyphs() {
+		let glyph = ttf_parser::GlyphId(glyph);
+		let mut expected = OutlineRecorder::default();
+		let mut actual = OutlineRecorder::default();
+		let expected_bbox = source.outline_glyph(glyph, &mut expected);
+		let actual_bbox = decoded.outline_glyph(glyph, &mut actual);
+		assert_eq!(actual_bbox, expected_bbox, "bbox of glyph {}", glyph.0);
+		assert_eq!(actual.0, expected.0, "outline of glyph {}", glyph.0);
+		assert_eq!(
+			decoded.glyph_hor_advance(glyph),
+			source.glyph_hor_advance(glyph)
+		);
+	}
+}
+
+#[test]
+fn woff_and_woff2_load_with_the_source_glyphs() {
+	let sfnt = std::fs::read(source_font()).expect("source font should be readable");
+	let source = load_font_from_file(source_font()).expect("source font should load");
+	let dir = tempfile::tempdir().expect("temp dir should be created");
+
+	for (name, encoded) in [
+		("font.woff", encode_woff1(&sfnt)),
+		("font.woff2", encode_woff2(&sfnt)),
+	] {
+		let path = dir.path().join(name);
+		std::fs::write(&path, encoded).expect("encoded font should be written");
+		let decoded = load_font_from_file(&path).expect("encoded font should load");
+		assert_same_font(&decoded, &source);
+	}
+}
@@ -1,9 +1,10 @@
 #![allow(dead_code)]
 
 use glyphweave::{
-	AlgorithmKind, CanvasConfig, CloudRequest, CloudResult, FontSizeSpec, RenderOptions, Rotation,
-	ShapeConfig, StyleConfig, WordEntry, generate, load_default_embedded_font, load_font_from_file,
-	mask::{build_shape_mask, calculate_auto_font_size, calculate_text_size},
+	AlgorithmKind, CanvasConfig, CloudRequest, CloudResult, CollisionMode, ColorStrategy,
+	FontSizeSpec, PlacementMode, RenderOptions, Rotation, ShapeConfig, ShapeSource, SizeScaling,
+	StyleConfig, SvgTextMode, WordEntry, generate, load_default_embedded_font, load_font_from_file,
+	mask::{calculate_text_size, resolve_shape_mask},
 };
 use std::path::Path;
 use std::sync::Arc;
@@ -36,13 +37,11 @@ pub fn build_request(algorithm: AlgorithmKind) -> CloudRequest {
 			height: 220,
 			margin: 8,
 		},
-		shape: ShapeConfig {
-			text: "RUST".to_string(),
-			font_size: FontSizeSpec::AutoFit,
-		},
+		shape: ShapeConfig::new(ShapeSource::Text("RUST".to_string()), FontSizeSpec::AutoFit),
 		words: sample_words(),
 		style: StyleConfig {
 			font_size_range: 10..=20,
+			size_scaling: SizeScaling::Uniform,
 			padding: 0,
 			colors: vec![
 				"#1D4ED8".to_string(),
@@ -50,16 +49,22 @@ pub fn build_request(algorithm: AlgorithmKind) -> CloudRequest {
 				"#059669".to_string(),
 				"#EA580C".to_string(),
 			],
-			rotations: vec![Rotation::Deg0],
+			color_strategy: ColorStrategy::Random,
+			rotations: vec![Rotation::DEG0],
+			rotation_range: None,
+			collision: CollisionMode::BoundingBox,
 		},
 		algorithm,
+		placement: PlacementMode::Weighted,
 		ratio_threshold: 0.2,
 		max_try_count: 260,
 		seed: Some(20260305),
 		font: Arc::new(font),
+		fallback_fonts: Vec::new(),
 		render: RenderOptions {
 			show_progress: false,
 			debug_mask_out: None,
+			svg_text: SvgTextMode::Text,
 		},
 	}
 }
@@ -75,24 +80,14 @@ pub fn generate_normalized(request: CloudRequest) -> (CloudRequest, CloudResult,
 }
 
 pub fn assert_placement_constraints(request: &CloudRequest, result: &CloudResult) {
-	let shape_font_size = match request.shape.font_size {
-		FontSizeSpec::Fixed(size) => size,
-		FontSizeSpec::AutoFit => {
-			calculate_auto_font_size(&request.canvas, &request.shape.text, request.font.as_ref())
-		}
-	};
-
-	let shape_mask = build_shape_mask(
-		&request.canvas,
-		&request.shape.text,
-		request.font.as_ref(),
-		shape_font_size,
-	);
+	let fonts = request.font_chain();
+	let (shape_mask, _) = resolve_shape_mask(&request.canvas, &request.shape, &fonts)
+		.expect("shape mask should build");
 
 	for (i, placement) in result.placements.iter().enumerate() {
 		let (w, h) = calculate_text_size(
 			&placement.word,
-			request.font.as_ref(),
+			&fonts,
 			placement.font_size,
 			request.style.padding,
 			placement.rotation,
@@ -133,7 +128,7 @@ pub fn assert_placement_constraints(request: &CloudRequest, result: &CloudResult
 		let a = &result.placements[i];
 		let (aw, ah) = calculate_text_size(
 			&a.word,
-			request.font.as_ref(),
+			&fonts,
 			a.font_size,
 			request.style.padding,
 			a.rotation,
@@ -143,7 +138,7 @@ pub fn assert_placement_constraints(request: &CloudRequest, result: &CloudResult
 			let b = &result.placements[j];
 			let (bw, bh) = calculate_text_size(
 				&b.word,
-				request.font.as_ref(),
+				&fonts,
 				b.font_size,
 				request.style.padding,
 				b.rotation,